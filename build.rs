@@ -0,0 +1,4 @@
+fn main() {
+    // the migrations are embedded into the binary, rebuild whenever one is added
+    println!("cargo:rerun-if-changed=db/migrations");
+}
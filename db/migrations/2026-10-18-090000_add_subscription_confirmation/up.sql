@@ -0,0 +1,16 @@
+ALTER TABLE subscriptions
+  ADD COLUMN status TEXT NOT NULL DEFAULT 'pending_confirmation'
+  CHECK (status IN ('pending_confirmation', 'confirmed'));
+
+-- subscriptions created before double opt-in existed are treated as confirmed
+UPDATE subscriptions SET status = 'confirmed';
+
+CREATE TABLE subscription_tokens (
+  token TEXT NOT NULL,
+  PRIMARY KEY (token),
+  subscription_id uuid NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+  created_at timestamptz NOT NULL,
+  expires_at timestamptz NOT NULL
+);
+
+CREATE INDEX subscription_tokens_subscription_id_idx ON subscription_tokens (subscription_id);
@@ -0,0 +1,2 @@
+ALTER TABLE subscriptions DROP COLUMN IF EXISTS newsletter_id;
+DROP TABLE IF EXISTS newsletters;
@@ -0,0 +1,21 @@
+CREATE TABLE newsletters (
+  id uuid NOT NULL,
+  PRIMARY KEY (id),
+  name TEXT NOT NULL UNIQUE,
+  description TEXT NOT NULL DEFAULT '',
+  created_at timestamptz NOT NULL
+);
+
+-- every free-text name used so far becomes a newsletter of its own
+INSERT INTO newsletters (id, name, created_at)
+SELECT gen_random_uuid(), name, MIN(subscribed_at) FROM subscriptions GROUP BY name;
+
+ALTER TABLE subscriptions
+  ADD COLUMN newsletter_id uuid REFERENCES newsletters (id) ON DELETE CASCADE;
+
+UPDATE subscriptions SET newsletter_id = newsletters.id
+FROM newsletters WHERE newsletters.name = subscriptions.name;
+
+ALTER TABLE subscriptions ALTER COLUMN newsletter_id SET NOT NULL;
+
+CREATE INDEX subscriptions_newsletter_id_idx ON subscriptions (newsletter_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS issue_deliveries;
+DROP TABLE IF EXISTS issues;
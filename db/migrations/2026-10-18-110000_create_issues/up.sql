@@ -0,0 +1,23 @@
+CREATE TABLE issues (
+  id uuid NOT NULL,
+  PRIMARY KEY (id),
+  newsletter_id uuid NOT NULL REFERENCES newsletters (id) ON DELETE CASCADE,
+  title TEXT NOT NULL,
+  text_body TEXT NOT NULL,
+  html_body TEXT NOT NULL,
+  created_at timestamptz NOT NULL,
+  published_at timestamptz
+);
+
+CREATE INDEX issues_newsletter_id_idx ON issues (newsletter_id);
+
+-- one row per recipient so an interrupted publish can pick up where it left off
+CREATE TABLE issue_deliveries (
+  issue_id uuid NOT NULL REFERENCES issues (id) ON DELETE CASCADE,
+  subscription_id uuid NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+  PRIMARY KEY (issue_id, subscription_id),
+  status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  last_error TEXT,
+  updated_at timestamptz NOT NULL
+);
@@ -0,0 +1,17 @@
+CREATE TABLE jobs (
+  id uuid NOT NULL,
+  PRIMARY KEY (id),
+  kind TEXT NOT NULL,
+  payload jsonb NOT NULL,
+  status TEXT NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'succeeded', 'dead')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  max_attempts INTEGER NOT NULL,
+  run_at timestamptz NOT NULL,
+  -- a running job whose lease has passed is considered abandoned and can be claimed again
+  locked_until timestamptz,
+  last_error TEXT,
+  created_at timestamptz NOT NULL,
+  updated_at timestamptz NOT NULL
+);
+
+CREATE INDEX jobs_status_run_at_idx ON jobs (status, run_at);
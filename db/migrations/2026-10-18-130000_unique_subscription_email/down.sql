@@ -0,0 +1 @@
+DROP INDEX IF EXISTS subscriptions_email_newsletter_idx;
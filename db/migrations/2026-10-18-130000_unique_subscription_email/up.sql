@@ -0,0 +1,14 @@
+-- keep one subscription per address and newsletter, preferring a confirmed one and then the oldest
+DELETE FROM subscriptions
+WHERE id IN (
+  SELECT id FROM (
+    SELECT id, row_number() OVER (
+      PARTITION BY lower(email), newsletter_id
+      ORDER BY (status = 'confirmed') DESC, subscribed_at, id
+    ) AS rank
+    FROM subscriptions
+  ) ranked
+  WHERE rank > 1
+);
+
+CREATE UNIQUE INDEX subscriptions_email_newsletter_idx ON subscriptions (lower(email), newsletter_id);
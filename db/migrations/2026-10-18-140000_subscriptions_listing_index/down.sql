@@ -0,0 +1 @@
+DROP INDEX IF EXISTS subscriptions_subscribed_at_id_idx;
@@ -0,0 +1,2 @@
+-- admin listings page through subscriptions in this order
+CREATE INDEX subscriptions_subscribed_at_id_idx ON subscriptions (subscribed_at, id);
@@ -0,0 +1,10 @@
+CREATE TABLE api_keys (
+  id uuid NOT NULL,
+  PRIMARY KEY (id),
+  name TEXT NOT NULL,
+  -- sha-256 of the key in hex; the key itself is only shown once, when minted
+  key_hash TEXT NOT NULL UNIQUE,
+  role TEXT NOT NULL CHECK (role IN ('admin', 'publisher', 'read_only')),
+  created_at timestamptz NOT NULL,
+  revoked_at timestamptz
+);
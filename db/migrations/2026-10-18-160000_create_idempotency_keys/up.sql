@@ -0,0 +1,17 @@
+CREATE TABLE idempotency_keys (
+  -- sha-256 of the caller's Authorization header, empty for anonymous callers, so one
+  -- caller can never replay another's response
+  caller TEXT NOT NULL,
+  key TEXT NOT NULL,
+  PRIMARY KEY (caller, key),
+  -- sha-256 of the method, path, query and body the key was first used with
+  request_hash TEXT NOT NULL,
+  -- the response is null while the first request is still being served
+  response_status SMALLINT,
+  response_headers JSONB,
+  response_body BYTEA,
+  created_at timestamptz NOT NULL,
+  expires_at timestamptz NOT NULL
+);
+
+CREATE INDEX idempotency_keys_expires_at ON idempotency_keys (expires_at);
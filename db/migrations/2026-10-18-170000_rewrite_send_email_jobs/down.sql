@@ -0,0 +1,3 @@
+-- the rewritten jobs keep their kind: the emails they carried cannot be rendered in sql,
+-- so drain the send_confirmation jobs before rolling the worker back
+SELECT 1;
@@ -0,0 +1,17 @@
+-- these jobs carried the rendered email, confirmation token and unsubscribe link included;
+-- each becomes a send_confirmation job for the subscription its token was issued to, which
+-- also takes the token out of the payloads of jobs that already ran
+UPDATE jobs
+SET kind = 'send_confirmation',
+    payload = jsonb_build_object(
+      'kind', 'send_confirmation',
+      'subscription_id', subscription_tokens.subscription_id
+    )
+FROM subscription_tokens
+WHERE jobs.kind = 'send_email'
+  AND subscription_tokens.token = substring(
+    jobs.payload -> 'message' ->> 'text_body' FROM 'confirm\?token=([0-9a-f]{32})'
+  );
+
+-- the rest lost their token to a confirmation or its expiry, so there is nothing left to
+-- send; they stay as they are and are dead-lettered once claimed, for operators to see
@@ -0,0 +1,8 @@
+ALTER TABLE subscriptions ADD COLUMN name TEXT;
+
+UPDATE subscriptions SET name = COALESCE(subscriptions.subscriber_name, newsletters.name)
+FROM newsletters
+WHERE newsletters.id = subscriptions.newsletter_id;
+
+ALTER TABLE subscriptions ALTER COLUMN name SET NOT NULL;
+ALTER TABLE subscriptions DROP COLUMN subscriber_name;
@@ -0,0 +1,10 @@
+-- `name` held the free-text newsletter title until newsletters got a table of their own,
+-- and the subscriber's name after that; rows still carrying their newsletter's title
+-- never had a subscriber name, so theirs stays null
+ALTER TABLE subscriptions ADD COLUMN subscriber_name TEXT;
+
+UPDATE subscriptions SET subscriber_name = subscriptions.name
+FROM newsletters
+WHERE newsletters.id = subscriptions.newsletter_id AND newsletters.name <> subscriptions.name;
+
+ALTER TABLE subscriptions DROP COLUMN name;
@@ -69,6 +69,125 @@ CREATE TABLE public.__diesel_schema_migrations (
 
 ALTER TABLE public.__diesel_schema_migrations OWNER TO postgres;
 
+--
+-- Name: api_keys; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.api_keys (
+    id uuid NOT NULL,
+    name text NOT NULL,
+    key_hash text NOT NULL,
+    role text NOT NULL,
+    created_at timestamp with time zone NOT NULL,
+    revoked_at timestamp with time zone,
+    CONSTRAINT api_keys_role_check CHECK ((role = ANY (ARRAY['admin'::text, 'publisher'::text, 'read_only'::text])))
+);
+
+
+ALTER TABLE public.api_keys OWNER TO postgres;
+
+--
+-- Name: idempotency_keys; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.idempotency_keys (
+    caller text NOT NULL,
+    key text NOT NULL,
+    request_hash text NOT NULL,
+    response_status smallint,
+    response_headers jsonb,
+    response_body bytea,
+    created_at timestamp with time zone NOT NULL,
+    expires_at timestamp with time zone NOT NULL
+);
+
+
+ALTER TABLE public.idempotency_keys OWNER TO postgres;
+
+--
+-- Name: issue_deliveries; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.issue_deliveries (
+    issue_id uuid NOT NULL,
+    subscription_id uuid NOT NULL,
+    status text DEFAULT 'pending'::text NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    last_error text,
+    updated_at timestamp with time zone NOT NULL,
+    CONSTRAINT issue_deliveries_status_check CHECK ((status = ANY (ARRAY['pending'::text, 'sent'::text, 'failed'::text])))
+);
+
+
+ALTER TABLE public.issue_deliveries OWNER TO postgres;
+
+--
+-- Name: issues; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.issues (
+    id uuid NOT NULL,
+    newsletter_id uuid NOT NULL,
+    title text NOT NULL,
+    text_body text NOT NULL,
+    html_body text NOT NULL,
+    created_at timestamp with time zone NOT NULL,
+    published_at timestamp with time zone
+);
+
+
+ALTER TABLE public.issues OWNER TO postgres;
+
+--
+-- Name: jobs; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.jobs (
+    id uuid NOT NULL,
+    kind text NOT NULL,
+    payload jsonb NOT NULL,
+    status text DEFAULT 'queued'::text NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    max_attempts integer NOT NULL,
+    run_at timestamp with time zone NOT NULL,
+    locked_until timestamp with time zone,
+    last_error text,
+    created_at timestamp with time zone NOT NULL,
+    updated_at timestamp with time zone NOT NULL,
+    CONSTRAINT jobs_status_check CHECK ((status = ANY (ARRAY['queued'::text, 'running'::text, 'succeeded'::text, 'dead'::text])))
+);
+
+
+ALTER TABLE public.jobs OWNER TO postgres;
+
+--
+-- Name: newsletters; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.newsletters (
+    id uuid NOT NULL,
+    name text NOT NULL,
+    description text DEFAULT ''::text NOT NULL,
+    created_at timestamp with time zone NOT NULL
+);
+
+
+ALTER TABLE public.newsletters OWNER TO postgres;
+
+--
+-- Name: subscription_tokens; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.subscription_tokens (
+    token text NOT NULL,
+    subscription_id uuid NOT NULL,
+    created_at timestamp with time zone NOT NULL,
+    expires_at timestamp with time zone NOT NULL
+);
+
+
+ALTER TABLE public.subscription_tokens OWNER TO postgres;
+
 --
 -- Name: subscriptions; Type: TABLE; Schema: public; Owner: postgres
 --
@@ -76,8 +195,11 @@ ALTER TABLE public.__diesel_schema_migrations OWNER TO postgres;
 CREATE TABLE public.subscriptions (
     id uuid NOT NULL,
     email text NOT NULL,
-    name text NOT NULL,
-    subscribed_at timestamp with time zone NOT NULL
+    subscribed_at timestamp with time zone NOT NULL,
+    status text DEFAULT 'pending_confirmation'::text NOT NULL,
+    newsletter_id uuid NOT NULL,
+    subscriber_name text,
+    CONSTRAINT subscriptions_status_check CHECK ((status = ANY (ARRAY['pending_confirmation'::text, 'confirmed'::text])))
 );
 
 
@@ -91,6 +213,78 @@ ALTER TABLE ONLY public.__diesel_schema_migrations
     ADD CONSTRAINT __diesel_schema_migrations_pkey PRIMARY KEY (version);
 
 
+--
+-- Name: api_keys api_keys_key_hash_key; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.api_keys
+    ADD CONSTRAINT api_keys_key_hash_key UNIQUE (key_hash);
+
+
+--
+-- Name: api_keys api_keys_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.api_keys
+    ADD CONSTRAINT api_keys_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: idempotency_keys idempotency_keys_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.idempotency_keys
+    ADD CONSTRAINT idempotency_keys_pkey PRIMARY KEY (caller, key);
+
+
+--
+-- Name: issue_deliveries issue_deliveries_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.issue_deliveries
+    ADD CONSTRAINT issue_deliveries_pkey PRIMARY KEY (issue_id, subscription_id);
+
+
+--
+-- Name: issues issues_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.issues
+    ADD CONSTRAINT issues_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: jobs jobs_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.jobs
+    ADD CONSTRAINT jobs_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: newsletters newsletters_name_key; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.newsletters
+    ADD CONSTRAINT newsletters_name_key UNIQUE (name);
+
+
+--
+-- Name: newsletters newsletters_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.newsletters
+    ADD CONSTRAINT newsletters_pkey PRIMARY KEY (id);
+
+
+--
+-- Name: subscription_tokens subscription_tokens_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.subscription_tokens
+    ADD CONSTRAINT subscription_tokens_pkey PRIMARY KEY (token);
+
+
 --
 -- Name: subscriptions subscriptions_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
 --
@@ -99,6 +293,95 @@ ALTER TABLE ONLY public.subscriptions
     ADD CONSTRAINT subscriptions_pkey PRIMARY KEY (id);
 
 
+--
+-- Name: idempotency_keys_expires_at; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX idempotency_keys_expires_at ON public.idempotency_keys USING btree (expires_at);
+
+
+--
+-- Name: issues_newsletter_id_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX issues_newsletter_id_idx ON public.issues USING btree (newsletter_id);
+
+
+--
+-- Name: jobs_status_run_at_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX jobs_status_run_at_idx ON public.jobs USING btree (status, run_at);
+
+
+--
+-- Name: subscription_tokens_subscription_id_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX subscription_tokens_subscription_id_idx ON public.subscription_tokens USING btree (subscription_id);
+
+
+--
+-- Name: subscriptions_email_newsletter_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE UNIQUE INDEX subscriptions_email_newsletter_idx ON public.subscriptions USING btree (lower(email), newsletter_id);
+
+
+--
+-- Name: subscriptions_newsletter_id_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX subscriptions_newsletter_id_idx ON public.subscriptions USING btree (newsletter_id);
+
+
+--
+-- Name: subscriptions_subscribed_at_id_idx; Type: INDEX; Schema: public; Owner: postgres
+--
+
+CREATE INDEX subscriptions_subscribed_at_id_idx ON public.subscriptions USING btree (subscribed_at, id);
+
+
+--
+-- Name: issue_deliveries issue_deliveries_issue_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.issue_deliveries
+    ADD CONSTRAINT issue_deliveries_issue_id_fkey FOREIGN KEY (issue_id) REFERENCES public.issues(id) ON DELETE CASCADE;
+
+
+--
+-- Name: issue_deliveries issue_deliveries_subscription_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.issue_deliveries
+    ADD CONSTRAINT issue_deliveries_subscription_id_fkey FOREIGN KEY (subscription_id) REFERENCES public.subscriptions(id) ON DELETE CASCADE;
+
+
+--
+-- Name: issues issues_newsletter_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.issues
+    ADD CONSTRAINT issues_newsletter_id_fkey FOREIGN KEY (newsletter_id) REFERENCES public.newsletters(id) ON DELETE CASCADE;
+
+
+--
+-- Name: subscription_tokens subscription_tokens_subscription_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.subscription_tokens
+    ADD CONSTRAINT subscription_tokens_subscription_id_fkey FOREIGN KEY (subscription_id) REFERENCES public.subscriptions(id) ON DELETE CASCADE;
+
+
+--
+-- Name: subscriptions subscriptions_newsletter_id_fkey; Type: FK CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.subscriptions
+    ADD CONSTRAINT subscriptions_newsletter_id_fkey FOREIGN KEY (newsletter_id) REFERENCES public.newsletters(id) ON DELETE CASCADE;
+
+
 --
 -- PostgreSQL database dump complete
 --
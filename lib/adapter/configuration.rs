@@ -1,64 +1,618 @@
 use std::env;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::domain::errors::DomainError;
+use config::{Config, Environment, File, Map};
+use lettre::message::Mailbox;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+
+/// which overlay in the configuration directory is layered on top of `base.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppEnvironment {
+    Local,
+    Test,
+    Production,
+}
+
+impl AppEnvironment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppEnvironment::Local => "local",
+            AppEnvironment::Test => "test",
+            AppEnvironment::Production => "production",
+        }
+    }
+}
+
+impl FromStr for AppEnvironment {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(AppEnvironment::Local),
+            "test" => Ok(AppEnvironment::Test),
+            "production" => Ok(AppEnvironment::Production),
+            other => Err(invalid(
+                "APP_ENVIRONMENT",
+                format!(
+                    "unknown environment {}, expected local, test or production",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AppEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// environment variables the service has always read, mapped onto their settings key
+const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("APP_HOST", "server.host"),
+    ("APP_PORT", "server.port"),
+    ("SHUTDOWN_DELAY_SECS", "server.shutdown_delay_secs"),
+    ("SHUTDOWN_DRAIN_TIMEOUT_SECS", "server.drain_timeout_secs"),
+    ("DATABASE_URL", "database.url"),
+    ("DB_USER", "database.username"),
+    ("DB_PASSWORD", "database.password"),
+    ("DB_HOST", "database.host"),
+    ("DB_PORT", "database.port"),
+    ("DB_NAME", "database.database_name"),
+    ("DB_SSL_MODE", "database.ssl_mode"),
+    ("DB_SSL_ROOT_CERT", "database.ssl_root_cert"),
+    ("DB_POOL_SIZE", "database.pool_size"),
+    ("DB_RUN_MIGRATIONS", "database.run_migrations"),
+    ("EMAIL_BACKEND", "email.backend"),
+    ("EMAIL_SENDER", "email.sender"),
+    ("SMTP_HOST", "email.smtp_host"),
+    ("SMTP_PORT", "email.smtp_port"),
+    ("SMTP_USERNAME", "email.smtp_username"),
+    ("SMTP_PASSWORD", "email.smtp_password"),
+    ("SMTP_TLS", "email.smtp_tls"),
+    ("SMTP_TIMEOUT_SECS", "email.smtp_timeout_secs"),
+    ("EMAIL_FILE_DIR", "email.file_directory"),
+    (
+        "SUBSCRIPTION_CONFIRMATION_TTL_SECS",
+        "subscription.confirmation_token_ttl_secs",
+    ),
+    ("APP_BASE_URL", "subscription.base_url"),
+    ("UNSUBSCRIBE_SECRET", "subscription.unsubscribe_secret"),
+    ("WORKER_POLL_INTERVAL_MS", "worker.poll_interval_ms"),
+    ("JOB_MAX_ATTEMPTS", "worker.max_attempts"),
+    ("JOB_RETRY_DELAY_SECS", "worker.retry_delay_secs"),
+    ("JOB_MAX_RETRY_DELAY_SECS", "worker.max_retry_delay_secs"),
+    ("JOB_LEASE_SECS", "worker.lease_secs"),
+    ("LOG_LEVEL", "logging.level"),
+    ("LOG_FORMAT", "logging.format"),
+    ("OTEL_EXPORTER_OTLP_ENDPOINT", "telemetry.otlp_endpoint"),
+    ("OTEL_SERVICE_NAME", "telemetry.service_name"),
+    (
+        "RATE_LIMIT_TRUSTED_PROXY_HOPS",
+        "rate_limit.trusted_proxy_hops",
+    ),
+    ("IDEMPOTENCY_TTL_SECS", "idempotency.ttl_secs"),
+];
+
+/// every setting of the service, layered from `base.toml`, the `<environment>.toml`
+/// overlay and finally environment variables
+#[derive(Debug, Clone, Deserialize)]
+pub struct Settings {
+    #[serde(skip, default = "default_environment")]
+    pub environment: AppEnvironment,
+    pub server: ServerConfiguration,
+    pub database: DatabaseConfiguration,
+    pub email: EmailConfiguration,
+    pub subscription: SubscriptionConfiguration,
+    pub worker: WorkerConfiguration,
+    pub logging: LoggingConfiguration,
+    pub telemetry: TelemetryConfiguration,
+    pub rate_limit: RateLimitConfiguration,
+    pub idempotency: IdempotencyConfiguration,
+}
+
+fn default_environment() -> AppEnvironment {
+    AppEnvironment::Local
+}
+
+impl Settings {
+    /// loads the settings for `APP_ENVIRONMENT` (default `local`) from `APP_CONFIG_DIR`
+    /// (default `configuration`) and the process environment
+    pub fn load() -> Result<Self, DomainError> {
+        let environment = env::var("APP_ENVIRONMENT")
+            .unwrap_or_else(|_| AppEnvironment::Local.as_str().to_string())
+            .parse()?;
+        let directory = env::var("APP_CONFIG_DIR").unwrap_or_else(|_| "configuration".to_string());
+        Self::load_from(Path::new(&directory), environment, env::vars().collect())
+    }
+
+    /// loads the settings from `directory`, taking overrides from `vars` instead of the
+    /// process environment; `APP__SECTION__KEY` overrides any key
+    pub fn load_from(
+        directory: &Path,
+        environment: AppEnvironment,
+        vars: Map<String, String>,
+    ) -> Result<Self, DomainError> {
+        let mut builder = Config::builder()
+            .add_source(File::from(directory.join("base.toml")))
+            .add_source(File::from(
+                directory.join(format!("{}.toml", environment.as_str())),
+            ))
+            .add_source(
+                Environment::with_prefix("APP")
+                    .prefix_separator("__")
+                    .separator("__")
+                    .source(Some(vars.clone())),
+            );
+        for (var, key) in ENV_OVERRIDES {
+            builder = builder
+                .set_override_option(*key, vars.get(*var).cloned())
+                .map_err(|err| invalid(key, err))?;
+        }
+
+        let mut settings: Settings = builder
+            .build()
+            .and_then(|cfg| cfg.try_deserialize())
+            .map_err(|err| {
+                invalid(
+                    "settings",
+                    format!(
+                        "failed to load {} settings from {} ({})",
+                        environment,
+                        directory.display(),
+                        err
+                    ),
+                )
+            })?;
+        settings.environment = environment;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    fn validate(&self) -> Result<(), DomainError> {
+        require(
+            !self.server.host.is_empty(),
+            "server.host",
+            "must not be empty",
+        )?;
+        require(self.server.port != 0, "server.port", "must not be 0")?;
+
+        self.database.validate()?;
+
+        self.email
+            .sender
+            .parse::<Mailbox>()
+            .map_err(|err| invalid("email.sender", format!("invalid address: {}", err)))?;
+        if self.email.backend == EmailBackend::Smtp {
+            require(
+                !self.email.smtp_host.is_empty(),
+                "email.smtp_host",
+                "must be set for the smtp backend",
+            )?;
+        }
+
+        let base_url = &self.subscription.base_url;
+        require(
+            base_url.starts_with("http://") || base_url.starts_with("https://"),
+            "subscription.base_url",
+            "must be an http(s) url",
+        )?;
+        require(
+            !self.subscription.unsubscribe_secret.is_empty(),
+            "subscription.unsubscribe_secret",
+            "must not be empty",
+        )?;
+
+        require(
+            self.worker.max_attempts > 0,
+            "worker.max_attempts",
+            "must be at least 1",
+        )?;
+        require(
+            !self.worker.poll_interval.is_zero(),
+            "worker.poll_interval_ms",
+            "must be greater than 0",
+        )?;
+
+        self.logging.level_filter()?;
+        self.telemetry.validate()?;
+        self.rate_limit.validate()?;
+
+        require(
+            !self.idempotency.ttl.is_zero(),
+            "idempotency.ttl_secs",
+            "must be greater than 0",
+        )?;
+        require(
+            !self.idempotency.in_progress_timeout.is_zero(),
+            "idempotency.in_progress_timeout_secs",
+            "must be greater than 0",
+        )
+    }
+}
+
+fn invalid(field: &str, message: impl fmt::Display) -> DomainError {
+    DomainError::Validation {
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn require(condition: bool, field: &str, message: &str) -> Result<(), DomainError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(invalid(field, message))
+    }
+}
+
+mod secs {
+    use serde::{Deserialize, Deserializer};
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+}
+
+mod millis {
+    use serde::{Deserialize, Deserializer};
+    use std::time::Duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerConfiguration {
+    pub host: String,
+    pub port: u16,
+    /// how long readiness reports unhealthy before the listener stops accepting, giving
+    /// load balancers time to stop routing new requests here
+    #[serde(rename = "shutdown_delay_secs", with = "secs")]
+    pub shutdown_delay: Duration,
+    /// how long in-flight requests and the job worker may take to finish on shutdown
+    #[serde(rename = "drain_timeout_secs", with = "secs")]
+    pub drain_timeout: Duration,
+}
+
+impl ServerConfiguration {
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// libpq `sslmode`, from never using TLS to requiring it with a verified server name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    Disable,
+    Allow,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Allow => "allow",
+            SslMode::Prefer => "prefer",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+}
+
+/// everything except the characters that are safe unescaped in a url's user info or path
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct DatabaseConfiguration {
+    /// full connection url, used instead of the individual fields below when set
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
     pub username: String,
+    #[serde(default)]
     pub password: String,
     pub port: u16,
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub database_name: String,
+    pub ssl_mode: SslMode,
+    /// CA certificate the server's certificate is verified against
+    #[serde(default)]
+    pub ssl_root_cert: Option<String>,
+    /// upper bound on open connections in the pool
+    pub pool_size: u32,
+    /// how long the readiness probe waits for a connection and a query
+    #[serde(rename = "health_check_timeout_ms", with = "millis")]
+    pub health_check_timeout: Duration,
+    /// apply pending embedded migrations when the application starts
+    pub run_migrations: bool,
 }
 
 impl DatabaseConfiguration {
-    pub fn new() -> Self {
-        let username: String = env::var("DB_USER").ok().unwrap_or("postgres".to_string());
-
-        let password: String = env::var("DB_PASSWORD")
-            .ok()
-            .unwrap_or("postgres".to_string());
-
-        let port: u16 = env::var("DB_PORT")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(5432);
-
-        let host = env::var("DB_HOST").ok().unwrap_or("localhost".to_string());
-
-        let db_name = env::var("DB_NAME").ok().unwrap_or("postgres".to_string());
-        DatabaseConfiguration {
-            username,
-            password,
-            port,
-            host,
-            database_name: db_name,
+    /// libpq connection url; ssl options already present in `url` take precedence
+    pub fn connection_string(&self) -> String {
+        let mut url = match &self.url {
+            Some(url) => url.clone(),
+            None => format!(
+                "postgres://{}:{}@{}:{}/{}",
+                utf8_percent_encode(&self.username, URL_COMPONENT),
+                utf8_percent_encode(&self.password, URL_COMPONENT),
+                self.host,
+                self.port,
+                utf8_percent_encode(&self.database_name, URL_COMPONENT)
+            ),
+        };
+
+        let mut params = Vec::new();
+        if !url.contains("sslmode=") {
+            params.push(format!("sslmode={}", self.ssl_mode.as_str()));
+        }
+        if let Some(root_cert) = &self.ssl_root_cert {
+            if !url.contains("sslrootcert=") {
+                params.push(format!(
+                    "sslrootcert={}",
+                    utf8_percent_encode(root_cert, URL_COMPONENT)
+                ));
+            }
+        }
+        if !params.is_empty() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&params.join("&"));
         }
+        url
     }
 
-    pub fn connection_string(&self) -> String {
-        format!(
-            "postgres://{}:{}@{}:{}/{}",
-            self.username, self.password, self.host, self.port, self.database_name
+    fn validate(&self) -> Result<(), DomainError> {
+        match &self.url {
+            Some(url) => require(
+                url.starts_with("postgres://") || url.starts_with("postgresql://"),
+                "database.url",
+                "must be a postgres:// or postgresql:// url",
+            )?,
+            None => {
+                let unless_url = "must be set unless database.url is";
+                require(!self.username.is_empty(), "database.username", unless_url)?;
+                require(!self.host.is_empty(), "database.host", unless_url)?;
+                require(self.port != 0, "database.port", "must not be 0")?;
+                require(
+                    !self.database_name.is_empty(),
+                    "database.database_name",
+                    unless_url,
+                )?;
+            }
+        }
+        if let Some(root_cert) = &self.ssl_root_cert {
+            require(
+                Path::new(root_cert).is_file(),
+                "database.ssl_root_cert",
+                "must point to an existing certificate file",
+            )?;
+        }
+        require(
+            self.pool_size > 0,
+            "database.pool_size",
+            "must be at least 1",
+        )?;
+        require(
+            !self.health_check_timeout.is_zero(),
+            "database.health_check_timeout_ms",
+            "must be greater than 0",
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubscriptionConfiguration {
+    /// how long a confirmation token stays valid after subscribing
+    #[serde(rename = "confirmation_token_ttl_secs", with = "secs")]
+    pub confirmation_token_ttl: Duration,
+    /// public url of this service, used to build links sent to subscribers
+    pub base_url: String,
+    /// key used to sign the unsubscribe links in every outbound email
+    pub unsubscribe_secret: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailBackend {
+    Smtp,
+    File,
+    Memory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    None,
+    StartTls,
+    Tls,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfiguration {
+    pub backend: EmailBackend,
+    pub sender: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_tls: SmtpTls,
+    #[serde(rename = "smtp_timeout_secs", with = "secs")]
+    pub smtp_timeout: Duration,
+    /// directory the file backend writes `.eml` files to
+    pub file_directory: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkerConfiguration {
+    /// how long the worker sleeps when the queue is empty
+    #[serde(rename = "poll_interval_ms", with = "millis")]
+    pub poll_interval: Duration,
+    pub max_attempts: i32,
+    /// delay before the first retry, doubled on every further attempt
+    #[serde(rename = "retry_delay_secs", with = "secs")]
+    pub retry_delay: Duration,
+    #[serde(rename = "max_retry_delay_secs", with = "secs")]
+    pub max_retry_delay: Duration,
+    /// how long a claimed job may run before another worker may take it over
+    #[serde(rename = "lease_secs", with = "secs")]
+    pub lease: Duration,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdempotencyConfiguration {
+    /// how long the response to a request with an `Idempotency-Key` is replayed to retries
+    #[serde(rename = "ttl_secs", with = "secs")]
+    pub ttl: Duration,
+    /// how long a retry is told the first request is still being served before it may
+    /// take the key over, e.g. after the service was restarted mid-request
+    #[serde(rename = "in_progress_timeout_secs", with = "secs")]
+    pub in_progress_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// human readable lines, one per event
+    Pretty,
+    /// one json object per event, carrying the fields of the spans it happened in
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoggingConfiguration {
+    /// most verbose level that is logged: error, warn, info, debug or trace
+    pub level: String,
+    pub format: LogFormat,
+}
+
+impl LoggingConfiguration {
+    pub fn level_filter(&self) -> Result<LevelFilter, DomainError> {
+        self.level
+            .parse()
+            .map_err(|err| invalid("logging.level", err))
+    }
+
+    /// `RUST_LOG` when it is set, so per-target directives can be used, otherwise `level`
+    pub fn env_filter(&self) -> EnvFilter {
+        EnvFilter::builder()
+            .with_default_directive(self.level_filter().unwrap_or(LevelFilter::INFO).into())
+            .from_env_lossy()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelemetryConfiguration {
+    /// base url of an OTLP/HTTP collector, e.g. `http://localhost:4318`; spans are only
+    /// exported when this is set and the service was built with the `otel` feature
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+}
+
+impl TelemetryConfiguration {
+    fn validate(&self) -> Result<(), DomainError> {
+        if let Some(endpoint) = &self.otlp_endpoint {
+            require(
+                endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+                "telemetry.otlp_endpoint",
+                "must be an http:// or https:// url",
+            )?;
+        }
+        require(
+            !self.service_name.is_empty(),
+            "telemetry.service_name",
+            "must not be empty",
         )
     }
 }
 
-impl Default for DatabaseConfiguration {
-    fn default() -> Self {
-        Self::new()
+/// a token bucket: `capacity` requests at once, then one more every `refill`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Quota {
+    pub capacity: u32,
+    #[serde(rename = "refill_secs", with = "secs")]
+    pub refill: Duration,
+}
+
+impl Quota {
+    fn validate(&self, field: &str) -> Result<(), DomainError> {
+        require(
+            self.capacity > 0,
+            &format!("{}.capacity", field),
+            "must be at least 1",
+        )?;
+        require(
+            !self.refill.is_zero(),
+            &format!("{}.refill_secs", field),
+            "must be greater than 0",
+        )
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// limits of a route that only knows who is calling; a missing quota means no limit
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RouteRateLimit {
+    pub per_ip: Option<Quota>,
+}
+
+/// `POST /subscribe` is also limited by the address being subscribed, so it cannot be
+/// used to flood someone with confirmation emails from many clients
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SubscribeRateLimit {
+    pub per_ip: Option<Quota>,
+    pub per_email: Option<Quota>,
+}
 
-    #[test]
-    fn test_cfg() {
-        dotenvy::dotenv().ok();
-        let cfg = DatabaseConfiguration::new();
-        assert_eq!(cfg.username, "postgres");
-        assert_eq!(cfg.password, "postgres");
-        assert_eq!(cfg.port, 5432);
-        assert_eq!(cfg.host, "localhost");
-        assert_eq!(cfg.database_name, "newsletter");
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RateLimitConfiguration {
+    /// proxies in front of the service that append the address they saw to
+    /// `X-Forwarded-For`; with none the peer address identifies the client, otherwise the
+    /// entry that many positions from the end does
+    pub trusted_proxy_hops: usize,
+    #[serde(default)]
+    pub subscribe: SubscribeRateLimit,
+    /// `GET /subscriptions/confirm`
+    #[serde(default)]
+    pub confirm: RouteRateLimit,
+    /// `GET` and `POST /unsubscribe`
+    #[serde(default)]
+    pub unsubscribe: RouteRateLimit,
+}
+
+impl RateLimitConfiguration {
+    fn validate(&self) -> Result<(), DomainError> {
+        let quotas = [
+            ("rate_limit.subscribe.per_ip", self.subscribe.per_ip),
+            ("rate_limit.subscribe.per_email", self.subscribe.per_email),
+            ("rate_limit.confirm.per_ip", self.confirm.per_ip),
+            ("rate_limit.unsubscribe.per_ip", self.unsubscribe.per_ip),
+        ];
+        for (field, quota) in quotas {
+            if let Some(quota) = quota {
+                quota.validate(field)?;
+            }
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use lettre::message::header::{ContentType, Header, HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::configuration::{EmailBackend, EmailConfiguration, SmtpTls};
+use crate::domain::errors::DomainError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+    /// one-click unsubscribe link advertised through the `List-Unsubscribe` headers
+    #[serde(default)]
+    pub unsubscribe_url: Option<String>,
+}
+
+/// `List-Unsubscribe` header from RFC 2369
+#[derive(Debug, Clone)]
+struct ListUnsubscribe(String);
+
+impl Header for ListUnsubscribe {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe")
+    }
+
+    fn parse(s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(
+            s.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string(),
+        ))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), format!("<{}>", self.0))
+    }
+}
+
+/// `List-Unsubscribe-Post` header from RFC 8058, marks the link as one-click
+#[derive(Debug, Clone)]
+struct ListUnsubscribePost;
+
+impl Header for ListUnsubscribePost {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe-Post")
+    }
+
+    fn parse(_s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), "List-Unsubscribe=One-Click".to_string())
+    }
+}
+
+pub trait EmailClient {
+    fn send_email(&self, message: &EmailMessage) -> Result<(), DomainError>;
+}
+
+/// builds the email client selected by `EMAIL_BACKEND`
+pub fn new_email_client(
+    cfg: &EmailConfiguration,
+) -> Result<Arc<dyn EmailClient + Send + Sync>, DomainError> {
+    match cfg.backend {
+        EmailBackend::Smtp => Ok(Arc::new(SmtpEmailClient::new(cfg)?)),
+        EmailBackend::File => Ok(Arc::new(FileEmailClient::new(cfg)?)),
+        EmailBackend::Memory => Ok(Arc::new(InMemoryEmailClient::new())),
+    }
+}
+
+fn build_message(sender: &Mailbox, message: &EmailMessage) -> Result<Message, DomainError> {
+    let to: Mailbox = message.to.parse().map_err(|err| DomainError::Validation {
+        field: "to".to_string(),
+        message: format!("invalid recipient address: {}", err),
+    })?;
+
+    let mut builder = Message::builder()
+        .from(sender.clone())
+        .to(to)
+        .subject(message.subject.clone());
+    if let Some(url) = &message.unsubscribe_url {
+        builder = builder
+            .header(ListUnsubscribe(url.clone()))
+            .header(ListUnsubscribePost);
+    }
+
+    builder
+        .multipart(
+            MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(message.text_body.clone()),
+                )
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(message.html_body.clone()),
+                ),
+        )
+        .map_err(|err| DomainError::Internal(format!("failed to build email (Error = {})", err)))
+}
+
+fn parse_sender(cfg: &EmailConfiguration) -> Result<Mailbox, DomainError> {
+    cfg.sender.parse().map_err(|err| {
+        DomainError::Internal(format!(
+            "invalid sender address {} (Error = {})",
+            cfg.sender, err
+        ))
+    })
+}
+
+pub struct SmtpEmailClient {
+    sender: Mailbox,
+    transport: SmtpTransport,
+}
+
+impl SmtpEmailClient {
+    pub fn new(cfg: &EmailConfiguration) -> Result<Self, DomainError> {
+        let sender = parse_sender(cfg)?;
+        let tls = match cfg.smtp_tls {
+            SmtpTls::None => Tls::None,
+            SmtpTls::StartTls | SmtpTls::Tls => {
+                let params = TlsParameters::new(cfg.smtp_host.clone()).map_err(|err| {
+                    DomainError::Internal(format!("invalid smtp tls settings (Error = {})", err))
+                })?;
+                if cfg.smtp_tls == SmtpTls::Tls {
+                    Tls::Wrapper(params)
+                } else {
+                    Tls::Required(params)
+                }
+            }
+        };
+
+        let mut builder = SmtpTransport::builder_dangerous(cfg.smtp_host.clone())
+            .port(cfg.smtp_port)
+            .tls(tls)
+            .timeout(Some(cfg.smtp_timeout));
+        if let (Some(username), Some(password)) = (&cfg.smtp_username, &cfg.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            sender,
+            transport: builder.build(),
+        })
+    }
+}
+
+impl EmailClient for SmtpEmailClient {
+    fn send_email(&self, message: &EmailMessage) -> Result<(), DomainError> {
+        let email = build_message(&self.sender, message)?;
+        self.transport.send(&email).map(|_| ()).map_err(|err| {
+            DomainError::Internal(format!("failed to send email over smtp (Error = {})", err))
+        })
+    }
+}
+
+/// writes every outbound message as an `.eml` file, handy for local development
+pub struct FileEmailClient {
+    sender: Mailbox,
+    directory: PathBuf,
+}
+
+impl FileEmailClient {
+    pub fn new(cfg: &EmailConfiguration) -> Result<Self, DomainError> {
+        let directory = PathBuf::from(&cfg.file_directory);
+        std::fs::create_dir_all(&directory).map_err(|err| {
+            DomainError::Internal(format!(
+                "failed to create email directory {} (Error = {})",
+                directory.display(),
+                err
+            ))
+        })?;
+
+        Ok(Self {
+            sender: parse_sender(cfg)?,
+            directory,
+        })
+    }
+}
+
+impl EmailClient for FileEmailClient {
+    fn send_email(&self, message: &EmailMessage) -> Result<(), DomainError> {
+        let email = build_message(&self.sender, message)?;
+        let path = self.directory.join(format!("{}.eml", Uuid::new_v4()));
+        std::fs::write(&path, email.formatted()).map_err(|err| {
+            DomainError::Internal(format!(
+                "failed to write email to {} (Error = {})",
+                path.display(),
+                err
+            ))
+        })
+    }
+}
+
+/// keeps outbound messages in memory so tests can inspect what would have been sent
+#[derive(Clone, Default)]
+pub struct InMemoryEmailClient {
+    sent: Arc<Mutex<Vec<EmailMessage>>>,
+}
+
+impl InMemoryEmailClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl EmailClient for InMemoryEmailClient {
+    fn send_email(&self, message: &EmailMessage) -> Result<(), DomainError> {
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
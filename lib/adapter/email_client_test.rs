@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use crate::adapter::configuration::{EmailBackend, EmailConfiguration, SmtpTls};
+    use crate::adapter::email_client::{
+        EmailClient, EmailMessage, FileEmailClient, InMemoryEmailClient, SmtpEmailClient,
+    };
+    use crate::domain::errors::DomainError;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn get_email_configuration(backend: EmailBackend) -> EmailConfiguration {
+        EmailConfiguration {
+            backend,
+            sender: "newsletter@localhost".to_string(),
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: 1025,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_tls: SmtpTls::None,
+            smtp_timeout: Duration::from_secs(5),
+            file_directory: std::env::temp_dir()
+                .join(format!("emails-{}", Uuid::new_v4()))
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    fn new_message(to: &str) -> EmailMessage {
+        EmailMessage {
+            to: to.to_string(),
+            subject: "Welcome aboard".to_string(),
+            html_body: "<p>hello</p>".to_string(),
+            text_body: "hello".to_string(),
+            unsubscribe_url: Some("http://localhost:3000/v1/unsubscribe?token=abc".to_string()),
+        }
+    }
+
+    /// accepts a single smtp session and hands back the raw DATA section
+    fn smtp_stand_in() -> (u16, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            writer
+                .write_all(b"220 localhost ESMTP stand-in\r\n")
+                .unwrap();
+            let mut data = String::new();
+            let mut in_data = false;
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 OK queued\r\n").unwrap();
+                    } else {
+                        data.push_str(&line);
+                    }
+                } else {
+                    let command = line.to_uppercase();
+                    if command.starts_with("EHLO") {
+                        writer
+                            .write_all(b"250-localhost\r\n250 8BITMIME\r\n")
+                            .unwrap();
+                    } else if command.starts_with("DATA") {
+                        in_data = true;
+                        writer.write_all(b"354 go ahead\r\n").unwrap();
+                    } else if command.starts_with("QUIT") {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    } else {
+                        writer.write_all(b"250 OK\r\n").unwrap();
+                    }
+                }
+                line.clear();
+            }
+            tx.send(data).unwrap();
+        });
+        (port, rx)
+    }
+
+    #[test]
+    fn smtp_send_email() {
+        // arrange
+        let (port, rx) = smtp_stand_in();
+        let mut cfg = get_email_configuration(EmailBackend::Smtp);
+        cfg.smtp_port = port;
+        let client = SmtpEmailClient::new(&cfg).unwrap();
+
+        // act
+        let result = client.send_email(&new_message("reader@example.com"));
+
+        // assert
+        assert!(result.is_ok());
+        let data = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(data.contains("To: reader@example.com"));
+        assert!(data.contains("Subject: Welcome aboard"));
+        assert!(data.contains("text/html"));
+        assert!(data.contains("List-Unsubscribe: <http://localhost:3000/v1/unsubscribe?token=abc>"));
+        assert!(data.contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click"));
+    }
+
+    #[test]
+    fn smtp_send_email_unreachable_server() {
+        // arrange
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut cfg = get_email_configuration(EmailBackend::Smtp);
+        cfg.smtp_port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let client = SmtpEmailClient::new(&cfg).unwrap();
+
+        // act
+        let result = client.send_email(&new_message("reader@example.com"));
+
+        // assert
+        assert!(matches!(result.unwrap_err(), DomainError::Internal(_)))
+    }
+
+    #[test]
+    fn file_send_email() {
+        // arrange
+        let cfg = get_email_configuration(EmailBackend::File);
+        let client = FileEmailClient::new(&cfg).unwrap();
+
+        // act
+        let result = client.send_email(&new_message("reader@example.com"));
+
+        // assert
+        assert!(result.is_ok());
+        let files: Vec<_> = std::fs::read_dir(&cfg.file_directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(1, files.len());
+        let contents = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(contents.contains("Subject: Welcome aboard"));
+        assert!(contents.contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click"));
+        std::fs::remove_dir_all(&cfg.file_directory).unwrap();
+    }
+
+    #[test]
+    fn file_send_email_invalid_recipient() {
+        // arrange
+        let cfg = get_email_configuration(EmailBackend::File);
+        let client = FileEmailClient::new(&cfg).unwrap();
+
+        // act
+        let result = client.send_email(&new_message("not an email"));
+
+        // assert
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::Validation { .. }
+        ));
+        std::fs::remove_dir_all(&cfg.file_directory).unwrap();
+    }
+
+    #[test]
+    fn in_memory_send_email() {
+        // arrange
+        let client = InMemoryEmailClient::new();
+        let message = new_message("reader@example.com");
+
+        // act
+        let result = client.send_email(&message);
+
+        // assert
+        assert!(result.is_ok());
+        assert_eq!(vec![message], client.sent());
+    }
+}
@@ -0,0 +1,882 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time;
+
+use crate::domain::errors::DomainError;
+use crate::domain::idempotency::{IdempotentRequest, Reservation, StoredResponse};
+use crate::domain::jobs as domain_jobs;
+use crate::model::models as api_models;
+
+use super::models::{
+    ApiKey, IdempotencyRecord, Issue, IssueDelivery, Job, Newsletter, Subscription,
+    SubscriptionToken,
+};
+use super::repository::{
+    ApiKeyRepository, ClaimedJob, DatabaseHealth, Deliver, HealthRepository, IdempotencyRepository,
+    IssueRepository, JobRepository, NewsletterRepository, PoolStatistics, Recipient,
+    SubscriptionCursor, SubscriptionFilter, SubscriptionRepository,
+};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+const DELIVERY_PENDING: &str = "pending";
+const DELIVERY_SENT: &str = "sent";
+const DELIVERY_FAILED: &str = "failed";
+
+#[derive(Default)]
+struct State {
+    newsletters: Vec<Newsletter>,
+    subscriptions: Vec<Subscription>,
+    tokens: Vec<SubscriptionToken>,
+    issues: Vec<Issue>,
+    deliveries: Vec<IssueDelivery>,
+    jobs: Vec<Job>,
+    api_keys: Vec<ApiKey>,
+    idempotency_keys: Vec<IdempotencyRecord>,
+}
+
+impl State {
+    /// drops the subscriptions `remove` picks along with the rows that reference them,
+    /// like the cascading foreign keys do
+    fn remove_subscriptions(&mut self, remove: impl Fn(&Subscription) -> bool) -> usize {
+        let removed: HashSet<Uuid> = self
+            .subscriptions
+            .iter()
+            .filter(|s| remove(s))
+            .map(|s| s.id)
+            .collect();
+        self.subscriptions.retain(|s| !removed.contains(&s.id));
+        self.tokens
+            .retain(|t| !removed.contains(&t.subscription_id));
+        self.deliveries
+            .retain(|d| !removed.contains(&d.subscription_id));
+        removed.len()
+    }
+}
+
+/// keeps every table in memory with the same semantics as the Postgres repository,
+/// so routes and the worker can be exercised without a database
+#[derive(Clone, Default)]
+pub struct InMemoryRepository {
+    state: Arc<Mutex<State>>,
+}
+
+impl InMemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+fn matches(filter: &SubscriptionFilter, sub: &Subscription) -> bool {
+    filter.email.as_ref().map_or(true, |email| {
+        sub.email.to_lowercase() == email.to_lowercase()
+    }) && filter
+        .newsletter_id
+        .map_or(true, |id| sub.newsletter_id == id)
+        && filter
+            .status
+            .map_or(true, |status| sub.status == status.as_str())
+        && filter
+            .subscribed_from
+            .map_or(true, |from| sub.subscribed_at >= from)
+        && filter
+            .subscribed_until
+            .map_or(true, |until| sub.subscribed_at < until)
+}
+
+#[async_trait]
+impl SubscriptionRepository for InMemoryRepository {
+    async fn add_subscription(
+        &self,
+        newsletter_id: Uuid,
+        name: String,
+        email: String,
+        subscribed_at: time::SystemTime,
+    ) -> Result<api_models::Subscription, DomainError> {
+        let mut state = self.state();
+        if !state.newsletters.iter().any(|n| n.id == newsletter_id) {
+            return Err(DomainError::NotFound(format!(
+                "newsletter not found for id = {}",
+                newsletter_id
+            )));
+        }
+        let duplicate = state.subscriptions.iter().any(|s| {
+            s.newsletter_id == newsletter_id && s.email.to_lowercase() == email.to_lowercase()
+        });
+        if duplicate {
+            return Err(DomainError::Conflict(format!(
+                "email is already subscribed to newsletter {}",
+                newsletter_id
+            )));
+        }
+
+        let subscription = Subscription {
+            id: Uuid::new_v4(),
+            email,
+            subscribed_at: subscribed_at.into(),
+            status: api_models::SubscriptionStatus::PendingConfirmation
+                .as_str()
+                .to_string(),
+            newsletter_id,
+            subscriber_name: Some(name),
+        };
+        state.subscriptions.push(subscription.clone());
+        Ok(api_models::Subscription::from(subscription))
+    }
+
+    async fn get_subscriptions(
+        &self,
+        email: String,
+        include_pending: bool,
+    ) -> Vec<api_models::Subscription> {
+        let email = email.to_lowercase();
+        self.state()
+            .subscriptions
+            .iter()
+            .filter(|s| s.email.to_lowercase() == email)
+            .filter(|s| {
+                include_pending || s.status == api_models::SubscriptionStatus::Confirmed.as_str()
+            })
+            .cloned()
+            .map(api_models::Subscription::from)
+            .collect()
+    }
+
+    async fn find_subscription(
+        &self,
+        newsletter_id: Uuid,
+        email: String,
+    ) -> Result<Option<api_models::Subscription>, DomainError> {
+        let email = email.to_lowercase();
+        Ok(self
+            .state()
+            .subscriptions
+            .iter()
+            .find(|s| s.newsletter_id == newsletter_id && s.email.to_lowercase() == email)
+            .cloned()
+            .map(api_models::Subscription::from))
+    }
+
+    async fn get_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError> {
+        let Some(sub) = self
+            .state()
+            .subscriptions
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+        else {
+            return Err(DomainError::NotFound(format!(
+                "subscription not found for id = {}",
+                id
+            )));
+        };
+
+        Ok(api_models::Subscription {
+            email: Some(sub.email.clone()),
+            ..api_models::Subscription::from(sub)
+        })
+    }
+
+    async fn remove_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError> {
+        let mut state = self.state();
+        let Some(index) = state.subscriptions.iter().position(|s| s.id == id) else {
+            return Err(DomainError::NotFound(format!(
+                "subscription not found for id = {}",
+                id
+            )));
+        };
+        let removed = state.subscriptions[index].clone();
+        state.remove_subscriptions(|s| s.id == id);
+
+        Ok(api_models::Subscription {
+            email: Some(removed.email.clone()),
+            ..api_models::Subscription::from(removed)
+        })
+    }
+
+    async fn create_confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        issued_at: time::SystemTime,
+        expires_at: time::SystemTime,
+    ) -> Result<String, DomainError> {
+        let mut state = self.state();
+        if !state.subscriptions.iter().any(|s| s.id == subscription_id) {
+            return Err(DomainError::Internal(format!(
+                "failed to store confirmation token (Error = unknown subscription {})",
+                subscription_id
+            )));
+        }
+
+        let token = Uuid::new_v4().simple().to_string();
+        state.tokens.push(SubscriptionToken {
+            token: token.clone(),
+            subscription_id,
+            created_at: issued_at.into(),
+            expires_at: expires_at.into(),
+        });
+        Ok(token)
+    }
+
+    async fn confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<Option<String>, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        Ok(self
+            .state()
+            .tokens
+            .iter()
+            .filter(|t| t.subscription_id == subscription_id && t.expires_at > now)
+            .max_by_key(|t| t.created_at)
+            .map(|t| t.token.clone()))
+    }
+
+    async fn confirm_subscription(
+        &self,
+        token: String,
+        confirmed_at: time::SystemTime,
+    ) -> Result<api_models::Subscription, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = confirmed_at.into();
+        let mut state = self.state();
+        let Some(index) = state
+            .tokens
+            .iter()
+            .position(|t| t.token == token && t.expires_at > now)
+        else {
+            return Err(DomainError::NotFound(
+                "confirmation token is invalid or has expired".to_string(),
+            ));
+        };
+        let consumed = state.tokens.remove(index);
+
+        let sub = state
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.id == consumed.subscription_id)
+            .ok_or_else(|| {
+                DomainError::Internal(format!(
+                    "failed to confirm subscription (Error = unknown subscription {})",
+                    consumed.subscription_id
+                ))
+            })?;
+        sub.status = api_models::SubscriptionStatus::Confirmed
+            .as_str()
+            .to_string();
+        Ok(api_models::Subscription::from(sub.clone()))
+    }
+
+    async fn remove_expired_confirmations(
+        &self,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        let mut state = self.state();
+        let expired: HashSet<Uuid> = state
+            .subscriptions
+            .iter()
+            .filter(|s| s.status == api_models::SubscriptionStatus::PendingConfirmation.as_str())
+            .filter(|s| {
+                let mut own = state.tokens.iter().filter(|t| t.subscription_id == s.id);
+                own.clone().any(|t| t.expires_at <= now) && !own.any(|t| t.expires_at > now)
+            })
+            .map(|s| s.id)
+            .collect();
+
+        let removed = state.remove_subscriptions(|s| expired.contains(&s.id));
+        state.tokens.retain(|t| t.expires_at > now);
+        Ok(removed)
+    }
+
+    async fn list_subscriptions(
+        &self,
+        filter: &SubscriptionFilter,
+        after: Option<SubscriptionCursor>,
+        limit: usize,
+    ) -> Result<Vec<api_models::Subscription>, DomainError> {
+        let mut subs: Vec<Subscription> = self
+            .state()
+            .subscriptions
+            .iter()
+            .filter(|s| matches(filter, s))
+            .filter(|s| {
+                after.map_or(true, |after| {
+                    (s.subscribed_at, s.id) > (after.subscribed_at, after.id)
+                })
+            })
+            .cloned()
+            .collect();
+        subs.sort_by_key(|s| (s.subscribed_at, s.id));
+
+        Ok(subs
+            .into_iter()
+            .take(limit)
+            .map(|sub| api_models::Subscription {
+                email: Some(sub.email.clone()),
+                ..api_models::Subscription::from(sub)
+            })
+            .collect())
+    }
+
+    async fn count_subscriptions(&self, filter: &SubscriptionFilter) -> Result<u64, DomainError> {
+        Ok(self
+            .state()
+            .subscriptions
+            .iter()
+            .filter(|s| matches(filter, s))
+            .count() as u64)
+    }
+}
+
+fn newsletter_not_found(id: Uuid) -> DomainError {
+    DomainError::NotFound(format!("newsletter not found for id = {}", id))
+}
+
+fn newsletter_name_taken(name: &str) -> DomainError {
+    DomainError::Conflict(format!("a newsletter named {} already exists", name))
+}
+
+#[async_trait]
+impl NewsletterRepository for InMemoryRepository {
+    async fn add_newsletter(
+        &self,
+        name: String,
+        description: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Newsletter, DomainError> {
+        let mut state = self.state();
+        if state.newsletters.iter().any(|n| n.name == name) {
+            return Err(newsletter_name_taken(&name));
+        }
+
+        let newsletter = Newsletter {
+            id: Uuid::new_v4(),
+            name,
+            description,
+            created_at: created_at.into(),
+        };
+        state.newsletters.push(newsletter.clone());
+        Ok(api_models::Newsletter::from(newsletter))
+    }
+
+    async fn get_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError> {
+        self.state()
+            .newsletters
+            .iter()
+            .find(|n| n.id == id)
+            .cloned()
+            .map(api_models::Newsletter::from)
+            .ok_or_else(|| newsletter_not_found(id))
+    }
+
+    async fn get_newsletters(&self) -> Result<Vec<api_models::Newsletter>, DomainError> {
+        let mut newsletters = self.state().newsletters.clone();
+        newsletters.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(newsletters
+            .into_iter()
+            .map(api_models::Newsletter::from)
+            .collect())
+    }
+
+    async fn update_newsletter(
+        &self,
+        id: Uuid,
+        name: String,
+        description: String,
+    ) -> Result<api_models::Newsletter, DomainError> {
+        let mut state = self.state();
+        if state
+            .newsletters
+            .iter()
+            .any(|n| n.id != id && n.name == name)
+        {
+            return Err(newsletter_name_taken(&name));
+        }
+
+        let newsletter = state
+            .newsletters
+            .iter_mut()
+            .find(|n| n.id == id)
+            .ok_or_else(|| newsletter_not_found(id))?;
+        newsletter.name = name;
+        newsletter.description = description;
+        Ok(api_models::Newsletter::from(newsletter.clone()))
+    }
+
+    async fn remove_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError> {
+        let mut state = self.state();
+        let Some(index) = state.newsletters.iter().position(|n| n.id == id) else {
+            return Err(newsletter_not_found(id));
+        };
+        let removed = state.newsletters.remove(index);
+        state.remove_subscriptions(|s| s.newsletter_id == id);
+        let issues: HashSet<Uuid> = state
+            .issues
+            .iter()
+            .filter(|i| i.newsletter_id == id)
+            .map(|i| i.id)
+            .collect();
+        state.issues.retain(|i| !issues.contains(&i.id));
+        state.deliveries.retain(|d| !issues.contains(&d.issue_id));
+        Ok(api_models::Newsletter::from(removed))
+    }
+}
+
+#[async_trait]
+impl IssueRepository for InMemoryRepository {
+    async fn add_issue(
+        &self,
+        newsletter_id: Uuid,
+        title: String,
+        text_body: String,
+        html_body: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Issue, DomainError> {
+        let mut state = self.state();
+        if !state.newsletters.iter().any(|n| n.id == newsletter_id) {
+            return Err(newsletter_not_found(newsletter_id));
+        }
+
+        let issue = Issue {
+            id: Uuid::new_v4(),
+            newsletter_id,
+            title,
+            text_body,
+            html_body,
+            created_at: created_at.into(),
+            published_at: None,
+        };
+        state.issues.push(issue.clone());
+        Ok(api_models::Issue::from(issue))
+    }
+
+    async fn get_issue(
+        &self,
+        newsletter_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<api_models::Issue, DomainError> {
+        self.state()
+            .issues
+            .iter()
+            .find(|i| i.id == issue_id && i.newsletter_id == newsletter_id)
+            .cloned()
+            .map(api_models::Issue::from)
+            .ok_or_else(|| {
+                DomainError::NotFound(format!(
+                    "issue {} not found for newsletter {}",
+                    issue_id, newsletter_id
+                ))
+            })
+    }
+
+    async fn enqueue_deliveries(
+        &self,
+        issue_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        let mut state = self.state();
+        let Some(issue) = state.issues.iter_mut().find(|i| i.id == issue_id) else {
+            return Err(DomainError::NotFound(format!(
+                "issue not found for id = {}",
+                issue_id
+            )));
+        };
+        issue.published_at.get_or_insert(now);
+        let newsletter_id = issue.newsletter_id;
+
+        let recipients: Vec<Uuid> = state
+            .subscriptions
+            .iter()
+            .filter(|s| s.newsletter_id == newsletter_id)
+            .filter(|s| s.status == api_models::SubscriptionStatus::Confirmed.as_str())
+            .filter(|s| {
+                !state
+                    .deliveries
+                    .iter()
+                    .any(|d| d.issue_id == issue_id && d.subscription_id == s.id)
+            })
+            .map(|s| s.id)
+            .collect();
+        for subscription_id in &recipients {
+            state.deliveries.push(IssueDelivery {
+                issue_id,
+                subscription_id: *subscription_id,
+                status: DELIVERY_PENDING.to_string(),
+                attempts: 0,
+                last_error: None,
+                updated_at: now,
+            });
+        }
+        Ok(recipients.len())
+    }
+
+    async fn deliver_pending(
+        &self,
+        issue_id: Uuid,
+        mut deliver: Deliver,
+    ) -> Result<(), DomainError> {
+        let recipients: Vec<Recipient> = {
+            let state = self.state();
+            state
+                .deliveries
+                .iter()
+                .filter(|d| d.issue_id == issue_id && d.status != DELIVERY_SENT)
+                .filter_map(|d| {
+                    let sub = state
+                        .subscriptions
+                        .iter()
+                        .find(|s| s.id == d.subscription_id)?;
+                    Some(Recipient {
+                        subscription_id: sub.id,
+                        email: sub.email.clone(),
+                    })
+                })
+                .collect()
+        };
+
+        // the lock is not held while sending, as `deliver` may take its time
+        for recipient in recipients {
+            let (status, last_error) = match deliver(&recipient) {
+                Ok(_) => (DELIVERY_SENT, None),
+                Err(err) => (DELIVERY_FAILED, Some(err.to_string())),
+            };
+            let mut state = self.state();
+            if let Some(delivery) = state
+                .deliveries
+                .iter_mut()
+                .find(|d| d.issue_id == issue_id && d.subscription_id == recipient.subscription_id)
+            {
+                delivery.status = status.to_string();
+                delivery.attempts += 1;
+                delivery.last_error = last_error;
+                delivery.updated_at = time::SystemTime::now().into();
+            }
+        }
+        Ok(())
+    }
+
+    async fn delivery_summary(
+        &self,
+        issue_id: Uuid,
+    ) -> Result<api_models::DeliverySummary, DomainError> {
+        let mut summary = api_models::DeliverySummary::default();
+        for delivery in self
+            .state()
+            .deliveries
+            .iter()
+            .filter(|d| d.issue_id == issue_id)
+        {
+            match delivery.status.as_str() {
+                DELIVERY_SENT => summary.sent += 1,
+                DELIVERY_FAILED => summary.failed += 1,
+                _ => summary.pending += 1,
+            }
+        }
+        Ok(summary)
+    }
+}
+
+#[async_trait]
+impl JobRepository for InMemoryRepository {
+    async fn enqueue_job(
+        &self,
+        job: &domain_jobs::Job,
+        max_attempts: i32,
+        run_at: time::SystemTime,
+    ) -> Result<Uuid, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = time::SystemTime::now().into();
+        let payload = serde_json::to_value(job)
+            .map_err(|err| DomainError::Internal(format!("failed to encode job: {}", err)))?;
+        let row = Job {
+            id: Uuid::new_v4(),
+            kind: job.kind().to_string(),
+            payload,
+            status: api_models::JobStatus::Queued.as_str().to_string(),
+            attempts: 0,
+            max_attempts,
+            run_at: run_at.into(),
+            locked_until: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let id = row.id;
+        self.state().jobs.push(row);
+        Ok(id)
+    }
+
+    async fn claim_job(
+        &self,
+        now: time::SystemTime,
+        lease: time::Duration,
+    ) -> Result<Option<ClaimedJob>, DomainError> {
+        let locked_until: chrono::DateTime<chrono::Utc> = (now + lease).into();
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        let mut state = self.state();
+        let due = state
+            .jobs
+            .iter_mut()
+            .filter(|job| {
+                (job.status == api_models::JobStatus::Queued.as_str() && job.run_at <= now)
+                    || (job.status == api_models::JobStatus::Running.as_str()
+                        && job.locked_until.is_some_and(|until| until < now))
+            })
+            .min_by_key(|job| job.run_at);
+        let Some(claimed) = due else {
+            return Ok(None);
+        };
+
+        claimed.attempts += 1;
+        claimed.updated_at = now;
+        match serde_json::from_value::<domain_jobs::Job>(claimed.payload.clone()) {
+            Ok(job) => {
+                claimed.status = api_models::JobStatus::Running.as_str().to_string();
+                claimed.locked_until = Some(locked_until);
+                Ok(Some(ClaimedJob {
+                    id: claimed.id,
+                    job,
+                    attempts: claimed.attempts,
+                    max_attempts: claimed.max_attempts,
+                }))
+            }
+            Err(err) => {
+                // a payload no worker understands will never succeed, retrying is pointless
+                let error = format!("failed to decode job payload: {}", err);
+                claimed.status = api_models::JobStatus::Dead.as_str().to_string();
+                claimed.locked_until = None;
+                claimed.last_error = Some(error.clone());
+                Err(DomainError::Internal(error))
+            }
+        }
+    }
+
+    async fn complete_job(&self, id: Uuid, now: time::SystemTime) -> Result<(), DomainError> {
+        if let Some(job) = self.state().jobs.iter_mut().find(|job| job.id == id) {
+            job.status = api_models::JobStatus::Succeeded.as_str().to_string();
+            job.locked_until = None;
+            job.last_error = None;
+            job.updated_at = now.into();
+        }
+        Ok(())
+    }
+
+    async fn fail_job(
+        &self,
+        id: Uuid,
+        error: String,
+        retry_at: time::SystemTime,
+        now: time::SystemTime,
+    ) -> Result<api_models::JobStatus, DomainError> {
+        let mut state = self.state();
+        let job = state
+            .jobs
+            .iter_mut()
+            .find(|job| job.id == id)
+            .ok_or_else(|| DomainError::NotFound(format!("job not found for id = {}", id)))?;
+        let status = if job.attempts >= job.max_attempts {
+            api_models::JobStatus::Dead
+        } else {
+            api_models::JobStatus::Queued
+        };
+        job.status = status.as_str().to_string();
+        job.run_at = retry_at.into();
+        job.locked_until = None;
+        job.last_error = Some(error);
+        job.updated_at = now.into();
+        Ok(status)
+    }
+
+    async fn get_jobs(
+        &self,
+        status: api_models::JobStatus,
+    ) -> Result<Vec<api_models::Job>, DomainError> {
+        let mut jobs: Vec<Job> = self
+            .state()
+            .jobs
+            .iter()
+            .filter(|job| job.status == status.as_str())
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.updated_at));
+        Ok(jobs.into_iter().map(api_models::Job::from).collect())
+    }
+
+    async fn requeue_job(
+        &self,
+        id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<api_models::Job, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        let mut state = self.state();
+        let job = state
+            .jobs
+            .iter_mut()
+            .find(|job| job.id == id && job.status == api_models::JobStatus::Dead.as_str())
+            .ok_or_else(|| DomainError::NotFound(format!("dead job not found for id = {}", id)))?;
+        job.status = api_models::JobStatus::Queued.as_str().to_string();
+        job.attempts = 0;
+        job.run_at = now;
+        job.updated_at = now;
+        Ok(api_models::Job::from(job.clone()))
+    }
+}
+
+#[async_trait]
+impl HealthRepository for InMemoryRepository {
+    /// always reachable, with every migration applied
+    async fn database_health(&self) -> DatabaseHealth {
+        DatabaseHealth {
+            pending_migrations: Some(Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    fn pool_statistics(&self) -> PoolStatistics {
+        PoolStatistics::default()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryRepository {
+    async fn add_api_key(
+        &self,
+        name: String,
+        role: api_models::ApiKeyRole,
+        key_hash: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError> {
+        let key = ApiKey {
+            id: Uuid::new_v4(),
+            name,
+            key_hash,
+            role: role.as_str().to_string(),
+            created_at: created_at.into(),
+            revoked_at: None,
+        };
+        self.state().api_keys.push(key.clone());
+        Ok(api_models::ApiKey::from(key))
+    }
+
+    async fn find_api_key(
+        &self,
+        key_hash: String,
+    ) -> Result<Option<api_models::ApiKey>, DomainError> {
+        Ok(self
+            .state()
+            .api_keys
+            .iter()
+            .find(|key| key.key_hash == key_hash && key.revoked_at.is_none())
+            .cloned()
+            .map(api_models::ApiKey::from))
+    }
+
+    async fn get_api_keys(&self) -> Result<Vec<api_models::ApiKey>, DomainError> {
+        let mut keys = self.state().api_keys.clone();
+        keys.sort_by_key(|key| key.created_at);
+        Ok(keys.into_iter().map(api_models::ApiKey::from).collect())
+    }
+
+    async fn revoke_api_key(
+        &self,
+        id: Uuid,
+        revoked_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError> {
+        let mut state = self.state();
+        let key = state
+            .api_keys
+            .iter_mut()
+            .find(|key| key.id == id)
+            .ok_or_else(|| DomainError::NotFound(format!("api key not found for id = {}", id)))?;
+        key.revoked_at.get_or_insert(revoked_at.into());
+        Ok(api_models::ApiKey::from(key.clone()))
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for InMemoryRepository {
+    async fn reserve_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        now: time::SystemTime,
+        ttl: time::Duration,
+        in_progress_timeout: time::Duration,
+    ) -> Result<Reservation, DomainError> {
+        let stale_before: chrono::DateTime<chrono::Utc> = now
+            .checked_sub(in_progress_timeout)
+            .unwrap_or(time::UNIX_EPOCH)
+            .into();
+        let created_at: chrono::DateTime<chrono::Utc> = now.into();
+        let expires_at: chrono::DateTime<chrono::Utc> = (now + ttl).into();
+        let mut state = self.state();
+        state
+            .idempotency_keys
+            .retain(|record| record.expires_at > created_at);
+
+        let Some(existing) = state
+            .idempotency_keys
+            .iter_mut()
+            .find(|record| record.caller == request.caller && record.key == request.key)
+        else {
+            state.idempotency_keys.push(IdempotencyRecord {
+                caller: request.caller,
+                key: request.key,
+                request_hash: request.request_hash,
+                response_status: None,
+                response_headers: None,
+                response_body: None,
+                created_at,
+                expires_at,
+            });
+            return Ok(Reservation::Started);
+        };
+        if existing.request_hash != request.request_hash {
+            return Ok(Reservation::PayloadMismatch);
+        }
+        if let Some(response) = existing.response() {
+            return Ok(Reservation::Completed(response));
+        }
+        if existing.created_at > stale_before {
+            return Ok(Reservation::InProgress);
+        }
+        existing.created_at = created_at;
+        existing.expires_at = expires_at;
+        Ok(Reservation::Started)
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        response: StoredResponse,
+    ) -> Result<(), DomainError> {
+        let status = i16::try_from(response.status)
+            .map_err(|_| DomainError::Internal(format!("invalid status {}", response.status)))?;
+        let headers = serde_json::to_value(&response.headers)
+            .map_err(|err| DomainError::Internal(format!("failed to encode headers: {}", err)))?;
+        if let Some(record) = self
+            .state()
+            .idempotency_keys
+            .iter_mut()
+            .find(|record| record.caller == request.caller && record.key == request.key)
+        {
+            record.response_status = Some(status);
+            record.response_headers = Some(headers);
+            record.response_body = Some(response.body);
+        }
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&self, request: IdempotentRequest) -> Result<(), DomainError> {
+        self.state().idempotency_keys.retain(|record| {
+            record.caller != request.caller
+                || record.key != request.key
+                || record.response_status.is_some()
+        });
+        Ok(())
+    }
+}
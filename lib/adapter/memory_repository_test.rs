@@ -0,0 +1,315 @@
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use std::time::{self, Duration};
+
+    use crate::adapter::memory_repository::InMemoryRepository;
+    use crate::adapter::repository::{
+        IdempotencyRepository, JobRepository, NewsletterRepository, SubscriptionCursor,
+        SubscriptionFilter, SubscriptionRepository,
+    };
+    use crate::domain::errors::DomainError;
+    use crate::domain::idempotency::{
+        Caller, IdempotencyKey, IdempotentRequest, Reservation, StoredResponse,
+    };
+    use crate::domain::jobs::Job;
+    use crate::model::models::{JobStatus, SubscriptionStatus};
+    use uuid::Uuid;
+
+    async fn new_repository() -> (InMemoryRepository, Uuid) {
+        let repo = InMemoryRepository::new();
+        let newsletter = repo
+            .add_newsletter(
+                "Weekly".to_string(),
+                "".to_string(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        let newsletter_id = Uuid::from_str(newsletter.newsletter_id.as_str()).unwrap();
+        (repo, newsletter_id)
+    }
+
+    async fn add_pending(repo: &InMemoryRepository, newsletter_id: Uuid, email: &str) -> Uuid {
+        let sub = repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                email.to_string(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        Uuid::from_str(sub.subscription_id.as_str()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn add_subscription_unknown_newsletter() {
+        // arrange
+        let (repo, _) = new_repository().await;
+
+        // act
+        let result = repo
+            .add_subscription(
+                Uuid::new_v4(),
+                "a".to_string(),
+                "a@example.com".to_string(),
+                time::SystemTime::now(),
+            )
+            .await;
+
+        // assert
+        assert!(matches!(result, Err(DomainError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn add_subscription_duplicate_email() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        add_pending(&repo, newsletter_id, "ursula@example.com").await;
+
+        // act
+        let result = repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                "Ursula@Example.com".to_string(),
+                time::SystemTime::now(),
+            )
+            .await;
+        let found = repo
+            .find_subscription(newsletter_id, "URSULA@example.com".to_string())
+            .await;
+
+        // assert
+        assert!(matches!(result, Err(DomainError::Conflict(_))));
+        assert!(found.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn get_subscriptions_filters_by_email_and_status() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        add_pending(&repo, newsletter_id, "ursula@example.com").await;
+        add_pending(&repo, newsletter_id, "octavia@example.com").await;
+
+        // act
+        let pending = repo
+            .get_subscriptions("ursula@example.com".to_string(), true)
+            .await;
+        let confirmed = repo
+            .get_subscriptions("ursula@example.com".to_string(), false)
+            .await;
+
+        // assert
+        assert_eq!(1, pending.len());
+        assert_eq!(SubscriptionStatus::PendingConfirmation, pending[0].status);
+        assert!(confirmed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_subscriptions_ignores_email_case() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        add_pending(&repo, newsletter_id, "Ursula@Example.com").await;
+
+        // act
+        let res = repo
+            .get_subscriptions("uRSULA@example.COM".to_string(), true)
+            .await;
+
+        // assert
+        assert_eq!(1, res.len());
+    }
+
+    #[tokio::test]
+    async fn list_subscriptions_filters_and_pages() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        let ursula = add_pending(&repo, newsletter_id, "ursula@example.com").await;
+        let octavia = add_pending(&repo, newsletter_id, "octavia@example.com").await;
+        let filter = SubscriptionFilter {
+            newsletter_id: Some(newsletter_id),
+            ..Default::default()
+        };
+
+        // act
+        let first = repo.list_subscriptions(&filter, None, 1).await.unwrap();
+        let after = SubscriptionCursor {
+            subscribed_at: first[0].subscribe_since,
+            id: Uuid::from_str(&first[0].subscription_id).unwrap(),
+        };
+        let second = repo
+            .list_subscriptions(&filter, Some(after), 1)
+            .await
+            .unwrap();
+        let by_email = repo
+            .count_subscriptions(&SubscriptionFilter {
+                email: Some("OCTAVIA@example.com".to_string()),
+                ..filter.clone()
+            })
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(ursula.to_string(), first[0].subscription_id);
+        assert_eq!(octavia.to_string(), second[0].subscription_id);
+        assert_eq!(1, by_email);
+    }
+
+    #[tokio::test]
+    async fn remove_subscription_not_found() {
+        // arrange
+        let (repo, _) = new_repository().await;
+
+        // act
+        let result = repo.remove_subscription(Uuid::new_v4()).await;
+
+        // assert
+        assert!(matches!(result, Err(DomainError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn confirm_subscription_token_is_single_use() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        let id = add_pending(&repo, newsletter_id, "ursula@example.com").await;
+        let now = time::SystemTime::now();
+        let token = repo
+            .create_confirmation_token(id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // act
+        let confirmed = repo.confirm_subscription(token.clone(), now).await;
+        let reused = repo.confirm_subscription(token, now).await;
+
+        // assert
+        assert_eq!(SubscriptionStatus::Confirmed, confirmed.unwrap().status);
+        assert!(matches!(reused, Err(DomainError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn remove_expired_confirmations() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        let now = time::SystemTime::now();
+        let expired = add_pending(&repo, newsletter_id, "expired@example.com").await;
+        let live = add_pending(&repo, newsletter_id, "live@example.com").await;
+        repo.create_confirmation_token(expired, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        repo.create_confirmation_token(live, now, now + Duration::from_secs(600))
+            .await
+            .unwrap();
+
+        // act
+        let removed = repo
+            .remove_expired_confirmations(now + Duration::from_secs(120))
+            .await;
+
+        // assert
+        assert_eq!(1, removed.unwrap());
+        assert!(repo.remove_subscription(expired).await.is_err());
+        assert!(repo.remove_subscription(live).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn add_newsletter_duplicate_name() {
+        // arrange
+        let (repo, _) = new_repository().await;
+
+        // act
+        let duplicate = repo
+            .add_newsletter(
+                "Weekly".to_string(),
+                "".to_string(),
+                time::SystemTime::now(),
+            )
+            .await;
+
+        // assert
+        assert!(matches!(duplicate, Err(DomainError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn remove_newsletter_removes_its_subscriptions() {
+        // arrange
+        let (repo, newsletter_id) = new_repository().await;
+        let id = add_pending(&repo, newsletter_id, "ursula@example.com").await;
+
+        // act
+        let removed = repo.remove_newsletter(newsletter_id).await;
+
+        // assert
+        assert!(removed.is_ok());
+        assert!(matches!(
+            repo.get_subscription(id).await,
+            Err(DomainError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_job_goes_dead_and_can_be_requeued() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let now = time::SystemTime::now();
+        let job = Job::SendConfirmation {
+            subscription_id: Uuid::new_v4(),
+        };
+        let id = repo.enqueue_job(&job, 1, now).await.unwrap();
+
+        // act
+        let claimed = repo.claim_job(now, Duration::from_secs(30)).await.unwrap();
+        let status = repo
+            .fail_job(id, "failed".to_string(), now, now)
+            .await
+            .unwrap();
+        let reclaimed = repo.claim_job(now, Duration::from_secs(30)).await.unwrap();
+        let requeued = repo.requeue_job(id, now).await.unwrap();
+
+        // assert
+        assert_eq!(Some(job), claimed.map(|claimed| claimed.job));
+        assert_eq!(JobStatus::Dead, status);
+        assert!(reclaimed.is_none());
+        assert_eq!(JobStatus::Queued, requeued.status);
+        assert_eq!(0, requeued.attempts);
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_replays_its_response() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let now = time::SystemTime::now();
+        let ttl = Duration::from_secs(60);
+        let key = IdempotencyKey::parse("retry-1").unwrap();
+        let request =
+            IdempotentRequest::new(&key, Caller::Authorized(b"Bearer k"), "POST", "/", b"{}");
+        let other =
+            IdempotentRequest::new(&key, Caller::Authorized(b"Bearer k"), "POST", "/", b"[]");
+        let response = StoredResponse {
+            status: 201,
+            headers: Vec::new(),
+            body: b"created".to_vec(),
+        };
+
+        // act
+        let first = repo
+            .reserve_idempotency_key(request.clone(), now, ttl, ttl)
+            .await;
+        let in_progress = repo
+            .reserve_idempotency_key(request.clone(), now, ttl, ttl)
+            .await;
+        repo.complete_idempotency_key(request.clone(), response.clone())
+            .await
+            .unwrap();
+        let retried = repo.reserve_idempotency_key(request, now, ttl, ttl).await;
+        let mismatch = repo.reserve_idempotency_key(other, now, ttl, ttl).await;
+
+        // assert
+        assert_eq!(Reservation::Started, first.unwrap());
+        assert_eq!(Reservation::InProgress, in_progress.unwrap());
+        assert_eq!(Reservation::Completed(response), retried.unwrap());
+        assert_eq!(Reservation::PayloadMismatch, mismatch.unwrap());
+    }
+}
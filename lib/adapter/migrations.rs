@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+use super::configuration::DatabaseConfiguration;
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::{Connection, PgConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+/// every migration under `db/migrations`, compiled into the binary
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("db/migrations");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationCommand {
+    /// applies every pending migration
+    Up,
+    /// reverts the most recently applied migration
+    Down,
+    /// lists every migration and whether it has been applied
+    Status,
+}
+
+impl FromStr for MigrationCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(MigrationCommand::Up),
+            "down" => Ok(MigrationCommand::Down),
+            "status" => Ok(MigrationCommand::Status),
+            other => Err(format!("unknown migrate command: {}", other)),
+        }
+    }
+}
+
+fn migration_error(action: &str, err: impl std::fmt::Display) -> DomainError {
+    DomainError::Internal(format!("failed to {} (Error = {})", action, err))
+}
+
+fn connect(cfg: &DatabaseConfiguration) -> Result<PgConnection, DomainError> {
+    PgConnection::establish(&cfg.connection_string())
+        .map_err(|err| migration_error("connect to the database", err))
+}
+
+/// applies every pending migration, returning the versions that were applied
+pub fn run_pending(cfg: &DatabaseConfiguration) -> Result<Vec<String>, DomainError> {
+    let mut conn = connect(cfg)?;
+    let applied = conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|err| migration_error("run pending migrations", err))?;
+    Ok(applied.iter().map(|v| v.to_string()).collect())
+}
+
+/// reverts the most recently applied migration, returning its version
+pub fn revert_last(cfg: &DatabaseConfiguration) -> Result<String, DomainError> {
+    let mut conn = connect(cfg)?;
+    conn.revert_last_migration(MIGRATIONS)
+        .map(|v| v.to_string())
+        .map_err(|err| migration_error("revert the last migration", err))
+}
+
+/// pairs the name of every embedded migration with whether it has been applied
+pub fn status(cfg: &DatabaseConfiguration) -> Result<Vec<(String, bool)>, DomainError> {
+    let mut conn = connect(cfg)?;
+    let applied: Vec<String> = conn
+        .applied_migrations()
+        .map_err(|err| migration_error("read applied migrations", err))?
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+    let migrations = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(|err| migration_error("read embedded migrations", err))?;
+
+    Ok(migrations
+        .iter()
+        .map(|m| {
+            let version = m.name().version().to_string();
+            (m.name().to_string(), applied.contains(&version))
+        })
+        .collect())
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod test {
+    use crate::adapter::configuration::Settings;
+    use crate::adapter::migrations::{self, MigrationCommand};
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Ok(MigrationCommand::Up), "up".parse());
+        assert_eq!(Ok(MigrationCommand::Down), "down".parse());
+        assert_eq!(Ok(MigrationCommand::Status), "status".parse());
+        assert!("sideways".parse::<MigrationCommand>().is_err());
+    }
+
+    #[test]
+    fn status_lists_every_embedded_migration() {
+        // arrange
+        dotenvy::dotenv().ok();
+        let cfg = Settings::load().unwrap().database;
+
+        // act
+        let status = migrations::status(&cfg).unwrap();
+
+        // assert
+        let on_disk = std::fs::read_dir("db/migrations").unwrap().count();
+        assert_eq!(on_disk, status.len());
+        assert!(status.iter().all(|(_, applied)| *applied));
+    }
+}
@@ -1,6 +1,13 @@
 pub mod configuration;
 pub(super) mod configuration_test;
+pub mod email_client;
+pub(super) mod email_client_test;
+pub mod memory_repository;
+pub(super) mod memory_repository_test;
+pub mod migrations;
+pub(super) mod migrations_test;
 pub mod models;
+pub mod query_spans;
 pub mod repository;
 pub(super) mod repository_test;
 pub mod schema;
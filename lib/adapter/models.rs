@@ -1,14 +1,195 @@
+use std::str::FromStr;
+
 use crate::adapter::schema;
+use crate::domain::idempotency::StoredResponse;
+use crate::model::models as api_models;
 use diesel::pg::Pg;
 use diesel::prelude::*;
 use uuid::Uuid;
 
+#[derive(Queryable, Insertable, Selectable, Identifiable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::newsletters)]
+#[diesel(check_for_backend(Pg))]
+pub struct Newsletter {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Queryable, Insertable, Selectable, Identifiable, Debug, PartialEq, Clone)]
 #[diesel(table_name = schema::subscriptions)]
 #[diesel(check_for_backend(Pg))]
 pub struct Subscription {
     pub id: Uuid,
     pub email: String,
-    pub name: String,
     pub subscribed_at: chrono::DateTime<chrono::Utc>,
+    pub status: String,
+    pub newsletter_id: Uuid,
+    pub subscriber_name: Option<String>,
+}
+
+#[derive(
+    Queryable, Insertable, Selectable, Identifiable, Associations, Debug, PartialEq, Clone,
+)]
+#[diesel(table_name = schema::subscription_tokens)]
+#[diesel(primary_key(token))]
+#[diesel(belongs_to(Subscription))]
+#[diesel(check_for_backend(Pg))]
+pub struct SubscriptionToken {
+    pub token: String,
+    pub subscription_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Identifiable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::issues)]
+#[diesel(check_for_backend(Pg))]
+pub struct Issue {
+    pub id: Uuid,
+    pub newsletter_id: Uuid,
+    pub title: String,
+    pub text_body: String,
+    pub html_body: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::issue_deliveries)]
+#[diesel(check_for_backend(Pg))]
+pub struct IssueDelivery {
+    pub issue_id: Uuid,
+    pub subscription_id: Uuid,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Identifiable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::jobs)]
+#[diesel(check_for_backend(Pg))]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: chrono::DateTime<chrono::Utc>,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Identifiable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::api_keys)]
+#[diesel(check_for_backend(Pg))]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_hash: String,
+    pub role: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Debug, PartialEq, Clone)]
+#[diesel(table_name = schema::idempotency_keys)]
+#[diesel(check_for_backend(Pg))]
+pub struct IdempotencyRecord {
+    pub caller: String,
+    pub key: String,
+    pub request_hash: String,
+    pub response_status: Option<i16>,
+    pub response_headers: Option<serde_json::Value>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl IdempotencyRecord {
+    /// the stored response, once the first request has been answered
+    pub fn response(&self) -> Option<StoredResponse> {
+        let status = u16::try_from(self.response_status?).ok()?;
+        let headers = self
+            .response_headers
+            .clone()
+            .and_then(|headers| serde_json::from_value(headers).ok())
+            .unwrap_or_default();
+        Some(StoredResponse {
+            status,
+            headers,
+            body: self.response_body.clone().unwrap_or_default(),
+        })
+    }
+}
+
+impl From<Subscription> for api_models::Subscription {
+    fn from(sub: Subscription) -> Self {
+        api_models::Subscription {
+            email: None,
+            subscription_id: sub.id.to_string(),
+            subscriber_name: sub.subscriber_name,
+            subscribe_since: sub.subscribed_at,
+            status: api_models::SubscriptionStatus::from_str(sub.status.as_str())
+                .unwrap_or_default(),
+            newsletter_id: sub.newsletter_id.to_string(),
+        }
+    }
+}
+
+impl From<ApiKey> for api_models::ApiKey {
+    fn from(key: ApiKey) -> Self {
+        api_models::ApiKey {
+            api_key_id: key.id.to_string(),
+            name: key.name,
+            role: api_models::ApiKeyRole::from_str(key.role.as_str()).unwrap_or_default(),
+            created_at: key.created_at,
+            revoked_at: key.revoked_at,
+        }
+    }
+}
+
+impl From<Newsletter> for api_models::Newsletter {
+    fn from(newsletter: Newsletter) -> Self {
+        api_models::Newsletter {
+            newsletter_id: newsletter.id.to_string(),
+            name: newsletter.name,
+            description: newsletter.description,
+            created_at: newsletter.created_at,
+        }
+    }
+}
+
+impl From<Issue> for api_models::Issue {
+    fn from(issue: Issue) -> Self {
+        api_models::Issue {
+            issue_id: issue.id.to_string(),
+            newsletter_id: issue.newsletter_id.to_string(),
+            title: issue.title,
+            text_body: issue.text_body,
+            html_body: issue.html_body,
+            created_at: issue.created_at,
+            published_at: issue.published_at,
+        }
+    }
+}
+
+impl From<Job> for api_models::Job {
+    fn from(job: Job) -> Self {
+        api_models::Job {
+            job_id: job.id.to_string(),
+            kind: job.kind,
+            status: api_models::JobStatus::from_str(job.status.as_str()).unwrap_or_default(),
+            attempts: job.attempts,
+            max_attempts: job.max_attempts,
+            last_error: job.last_error,
+            run_at: job.run_at,
+            created_at: job.created_at,
+        }
+    }
 }
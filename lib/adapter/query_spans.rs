@@ -0,0 +1,41 @@
+use diesel::connection::{Instrumentation, InstrumentationEvent};
+use tracing::{field, info_span, Span};
+
+/// opens a `db.query` span for every statement a connection runs, as a child of whatever
+/// span is entered on the thread running it
+#[derive(Default)]
+pub struct QuerySpans {
+    open: Vec<Span>,
+}
+
+/// the sql of a diesel debug query without its bind values, which may hold subscriber data
+fn statement(query: &dyn std::fmt::Display) -> String {
+    let query = query.to_string();
+    match query.split_once(" -- binds: ") {
+        Some((sql, _)) => sql.to_string(),
+        None => query,
+    }
+}
+
+impl Instrumentation for QuerySpans {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { query, .. } => {
+                self.open.push(info_span!(
+                    "db.query",
+                    db.system = "postgresql",
+                    db.statement = statement(query),
+                    otel.status_code = field::Empty,
+                    error = field::Empty,
+                ));
+            }
+            InstrumentationEvent::FinishQuery { error, .. } => {
+                if let (Some(span), Some(err)) = (self.open.pop(), error) {
+                    span.record("otel.status_code", "ERROR");
+                    span.record("error", field::display(err));
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,28 +1,297 @@
+use std::str::FromStr;
 use std::time;
 
 use crate::domain::errors::DomainError;
+use crate::domain::idempotency::{IdempotentRequest, Reservation, StoredResponse};
+use crate::domain::jobs as domain_jobs;
 use crate::model::models as api_models;
 
-use super::schema::subscriptions;
+use super::migrations::MIGRATIONS;
+use super::models::{
+    ApiKey, IdempotencyRecord, Issue, IssueDelivery, Job, Newsletter, SubscriptionToken,
+};
+use super::query_spans::QuerySpans;
+use super::schema::{
+    api_keys, idempotency_keys, issue_deliveries, issues, jobs, newsletters, subscription_tokens,
+    subscriptions,
+};
 use super::{configuration::DatabaseConfiguration, models::Subscription};
+use async_trait::async_trait;
+use diesel::pg::Pg;
 use diesel::prelude::*;
-use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use diesel::result::DatabaseErrorKind;
+use diesel_migrations::MigrationHarness;
 use uuid::Uuid;
 
-pub trait SubscriptionRepository {
-    fn add_subscription(
+diesel::define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// narrows a subscription listing; every field that is set must match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubscriptionFilter {
+    /// matched ignoring case
+    pub email: Option<String>,
+    pub newsletter_id: Option<Uuid>,
+    pub status: Option<api_models::SubscriptionStatus>,
+    /// inclusive lower bound on `subscribed_at`
+    pub subscribed_from: Option<chrono::DateTime<chrono::Utc>>,
+    /// exclusive upper bound on `subscribed_at`
+    pub subscribed_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// the last subscription of a page; listings are ordered by `subscribed_at`, then id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionCursor {
+    pub subscribed_at: chrono::DateTime<chrono::Utc>,
+    pub id: Uuid,
+}
+
+#[async_trait]
+pub trait SubscriptionRepository: Send + Sync {
+    async fn add_subscription(
         &self,
+        newsletter_id: Uuid,
         name: String,
         email: String,
         subcribed_at: time::SystemTime,
     ) -> Result<api_models::Subscription, DomainError>;
-    fn get_subscriptions(&mut self, email: String) -> Vec<api_models::Subscription>;
-    fn remove_subscription(&mut self, id: Uuid) -> Result<api_models::Subscription, DomainError>;
+    async fn get_subscriptions(
+        &self,
+        email: String,
+        include_pending: bool,
+    ) -> Vec<api_models::Subscription>;
+    /// looks up the subscription of an address to a newsletter, ignoring the email's case
+    async fn find_subscription(
+        &self,
+        newsletter_id: Uuid,
+        email: String,
+    ) -> Result<Option<api_models::Subscription>, DomainError>;
+    /// the subscription with `id`, along with its email
+    async fn get_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError>;
+    async fn remove_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError>;
+    /// issues a single-use token that confirms the pending subscription until `expires_at`
+    async fn create_confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        issued_at: time::SystemTime,
+        expires_at: time::SystemTime,
+    ) -> Result<String, DomainError>;
+    /// the token most recently issued for the subscription, unless it has expired by `now`
+    async fn confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<Option<String>, DomainError>;
+    /// consumes the token and marks its subscription as confirmed
+    async fn confirm_subscription(
+        &self,
+        token: String,
+        confirmed_at: time::SystemTime,
+    ) -> Result<api_models::Subscription, DomainError>;
+    /// drops pending subscriptions whose tokens have all expired along with any other
+    /// expired tokens, returning the number of subscriptions removed
+    async fn remove_expired_confirmations(
+        &self,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError>;
+    /// up to `limit` subscriptions matching `filter` that come after `after`, oldest first
+    /// and with their email
+    async fn list_subscriptions(
+        &self,
+        filter: &SubscriptionFilter,
+        after: Option<SubscriptionCursor>,
+        limit: usize,
+    ) -> Result<Vec<api_models::Subscription>, DomainError>;
+    /// how many subscriptions match `filter` across every page
+    async fn count_subscriptions(&self, filter: &SubscriptionFilter) -> Result<u64, DomainError>;
+}
+
+#[async_trait]
+pub trait NewsletterRepository: Send + Sync {
+    async fn add_newsletter(
+        &self,
+        name: String,
+        description: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Newsletter, DomainError>;
+    async fn get_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError>;
+    async fn get_newsletters(&self) -> Result<Vec<api_models::Newsletter>, DomainError>;
+    async fn update_newsletter(
+        &self,
+        id: Uuid,
+        name: String,
+        description: String,
+    ) -> Result<api_models::Newsletter, DomainError>;
+    /// removes the newsletter together with all of its subscriptions
+    async fn remove_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError>;
+}
+
+/// subscriber an issue still has to be delivered to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipient {
+    pub subscription_id: Uuid,
+    pub email: String,
+}
+
+/// sends one delivery; runs on the blocking pool while the delivery row is locked
+pub type Deliver = Box<dyn FnMut(&Recipient) -> Result<(), DomainError> + Send>;
+
+#[async_trait]
+pub trait IssueRepository: Send + Sync {
+    async fn add_issue(
+        &self,
+        newsletter_id: Uuid,
+        title: String,
+        text_body: String,
+        html_body: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Issue, DomainError>;
+    async fn get_issue(
+        &self,
+        newsletter_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<api_models::Issue, DomainError>;
+    /// records a pending delivery for every confirmed subscriber that does not have one yet
+    /// and marks the issue as published, returning the number of deliveries added
+    async fn enqueue_deliveries(
+        &self,
+        issue_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError>;
+    /// hands every delivery that has not been sent yet to `deliver`, holding a row lock
+    /// while doing so, so concurrent or resumed publishes never send the same one twice
+    async fn deliver_pending(&self, issue_id: Uuid, deliver: Deliver) -> Result<(), DomainError>;
+    async fn delivery_summary(
+        &self,
+        issue_id: Uuid,
+    ) -> Result<api_models::DeliverySummary, DomainError>;
+}
+
+/// job claimed by a worker; the claim holds until the lease expires
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimedJob {
+    pub id: Uuid,
+    pub job: domain_jobs::Job,
+    pub attempts: i32,
+    pub max_attempts: i32,
+}
+
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    async fn enqueue_job(
+        &self,
+        job: &domain_jobs::Job,
+        max_attempts: i32,
+        run_at: time::SystemTime,
+    ) -> Result<Uuid, DomainError>;
+    /// claims the next due job with `FOR UPDATE SKIP LOCKED` so concurrent workers never
+    /// pick the same one, including running jobs whose lease has expired
+    async fn claim_job(
+        &self,
+        now: time::SystemTime,
+        lease: time::Duration,
+    ) -> Result<Option<ClaimedJob>, DomainError>;
+    async fn complete_job(&self, id: Uuid, now: time::SystemTime) -> Result<(), DomainError>;
+    /// schedules another attempt at `retry_at`, or moves the job to the dead-letter state
+    /// once it has used up its attempts
+    async fn fail_job(
+        &self,
+        id: Uuid,
+        error: String,
+        retry_at: time::SystemTime,
+        now: time::SystemTime,
+    ) -> Result<api_models::JobStatus, DomainError>;
+    async fn get_jobs(
+        &self,
+        status: api_models::JobStatus,
+    ) -> Result<Vec<api_models::Job>, DomainError>;
+    /// puts a dead job back on the queue with a fresh set of attempts
+    async fn requeue_job(
+        &self,
+        id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<api_models::Job, DomainError>;
+}
+
+/// connection pool counters at the time they were read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStatistics {
+    pub connections: u32,
+    pub idle_connections: u32,
+    pub max_size: u32,
+}
+
+/// what the readiness probe found out about the database
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatabaseHealth {
+    /// why a pooled connection could not run a query in time, if it could not
+    pub error: Option<String>,
+    pub latency: time::Duration,
+    pub pool: PoolStatistics,
+    /// migrations embedded in the binary that the database has not applied yet
+    pub pending_migrations: Option<Vec<String>>,
+}
+
+#[async_trait]
+pub trait HealthRepository: Send + Sync {
+    /// checks out a pooled connection and runs a trivial query, giving up after the
+    /// configured health check timeout
+    async fn database_health(&self) -> DatabaseHealth;
+
+    /// the pool counters as they are right now, without touching the database
+    fn pool_statistics(&self) -> PoolStatistics;
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// stores a key under its hash; the key itself is never stored
+    async fn add_api_key(
+        &self,
+        name: String,
+        role: api_models::ApiKeyRole,
+        key_hash: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError>;
+    /// the key with `key_hash`, unless it has been revoked
+    async fn find_api_key(
+        &self,
+        key_hash: String,
+    ) -> Result<Option<api_models::ApiKey>, DomainError>;
+    async fn get_api_keys(&self) -> Result<Vec<api_models::ApiKey>, DomainError>;
+    /// revoking a key again keeps the time it was first revoked
+    async fn revoke_api_key(
+        &self,
+        id: Uuid,
+        revoked_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError>;
+}
+
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// claims the key of `request` until `now + ttl`, or tells how an earlier request with
+    /// it went; a claim whose request was not answered within `in_progress_timeout` is
+    /// taken over, as whoever held it is gone
+    async fn reserve_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        now: time::SystemTime,
+        ttl: time::Duration,
+        in_progress_timeout: time::Duration,
+    ) -> Result<Reservation, DomainError>;
+    /// stores the answer to the request that claimed the key
+    async fn complete_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        response: StoredResponse,
+    ) -> Result<(), DomainError>;
+    /// gives up an unanswered claim so a retry can take it
+    async fn release_idempotency_key(&self, request: IdempotentRequest) -> Result<(), DomainError>;
 }
 
 #[derive(Clone)]
 pub struct Repository {
     pool: Pool<ConnectionManager<PgConnection>>,
+    health_check_timeout: time::Duration,
 }
 
 pub(super) fn connection_pool(
@@ -34,7 +303,7 @@ pub(super) fn connection_pool(
     loop {
         let manager = ConnectionManager::<PgConnection>::new(cfg.connection_string());
         match Pool::builder()
-            .max_size(10)
+            .max_size(cfg.pool_size)
             .test_on_check_out(true)
             .build(manager)
         {
@@ -58,17 +327,73 @@ pub(super) fn connection_pool(
     }
 }
 
+/// restricts `query` to the subscriptions matching `filter`
+fn filter_subscriptions<'a, ST>(
+    mut query: subscriptions::BoxedQuery<'a, Pg, ST>,
+    filter: &SubscriptionFilter,
+) -> subscriptions::BoxedQuery<'a, Pg, ST> {
+    if let Some(email) = &filter.email {
+        query = query.filter(lower(subscriptions::email).eq(lower(email.clone())));
+    }
+    if let Some(newsletter_id) = filter.newsletter_id {
+        query = query.filter(subscriptions::newsletter_id.eq(newsletter_id));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(subscriptions::status.eq(status.as_str()));
+    }
+    if let Some(from) = filter.subscribed_from {
+        query = query.filter(subscriptions::subscribed_at.ge(from));
+    }
+    if let Some(until) = filter.subscribed_until {
+        query = query.filter(subscriptions::subscribed_at.lt(until));
+    }
+    query
+}
+
 impl Repository {
     pub fn new(cfg: &DatabaseConfiguration) -> Result<Self, diesel::r2d2::PoolError> {
         Ok(Self {
             pool: connection_pool(cfg),
+            health_check_timeout: cfg.health_check_timeout,
         })
     }
+
+    fn connection(&self) -> Result<PooledConnection<ConnectionManager<PgConnection>>, DomainError> {
+        self.pool.get().map_err(|err| {
+            DomainError::Internal(format!(
+                "Database Error! Failed to get connection (Err={})",
+                err
+            ))
+        })
+    }
+
+    /// runs `query` with a pooled connection on tokio's blocking pool, so diesel's
+    /// synchronous calls never stall the async executor. The checkout and every statement
+    /// get a span under the caller's, e.g. the request being served
+    async fn run<T, F>(&self, query: F) -> Result<T, DomainError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut PgConnection) -> Result<T, DomainError> + Send + 'static,
+    {
+        let repo = self.clone();
+        let parent = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _parent = parent.enter();
+            let mut conn =
+                tracing::info_span!("db.pool.checkout").in_scope(|| repo.connection())?;
+            conn.set_instrumentation(QuerySpans::default());
+            query(&mut conn)
+        })
+        .await
+        .map_err(|err| DomainError::Internal(format!("database task failed (Error = {})", err)))?
+    }
 }
 
+#[async_trait]
 impl SubscriptionRepository for Repository {
-    fn add_subscription(
+    async fn add_subscription(
         &self,
+        newsletter_id: Uuid,
         name: String,
         email: String,
         subscribed_at: time::SystemTime,
@@ -77,101 +402,1106 @@ impl SubscriptionRepository for Repository {
         let subscription = Subscription {
             id,
             email,
-            name,
             subscribed_at: subscribed_at.into(),
+            status: api_models::SubscriptionStatus::PendingConfirmation
+                .as_str()
+                .to_string(),
+            newsletter_id,
+            subscriber_name: Some(name),
         };
 
-        let pool = &mut self.pool.get().map_err(|err| {
-            DomainError::Internal(format!(
-                "Database Error! Failed to get connection (Err={})",
-                err
-            ))
-        })?;
+        self.run(move |conn| {
+            diesel::insert_into(subscriptions::table)
+                .values(&subscription)
+                .execute(conn)
+                .map(|_| api_models::Subscription::from(subscription))
+                .map_err(|err| match err {
+                    diesel::result::Error::DatabaseError(
+                        DatabaseErrorKind::ForeignKeyViolation,
+                        _,
+                    ) => DomainError::NotFound(format!(
+                        "newsletter not found for id = {}",
+                        newsletter_id
+                    )),
+                    diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                        DomainError::Conflict(format!(
+                            "email is already subscribed to newsletter {}",
+                            newsletter_id
+                        ))
+                    }
+                    err => DomainError::Internal(format!(
+                        "failed to store new subscription (Error = {})",
+                        err
+                    )),
+                })
+        })
+        .await
+    }
+
+    async fn get_subscriptions(
+        &self,
+        email: String,
+        include_pending: bool,
+    ) -> Vec<api_models::Subscription> {
+        self.run(move |conn| {
+            let mut query = subscriptions::table
+                .filter(lower(subscriptions::email).eq(lower(email)))
+                .select(Subscription::as_select())
+                .into_boxed();
+            if !include_pending {
+                query = query.filter(
+                    subscriptions::status.eq(api_models::SubscriptionStatus::Confirmed.as_str()),
+                );
+            }
+            let subs: Vec<Subscription> = query.load(conn).unwrap_or_else(|_| Vec::new());
+
+            Ok(subs
+                .into_iter()
+                .map(api_models::Subscription::from)
+                .collect())
+        })
+        .await
+        .unwrap_or_else(|_| Vec::new())
+    }
+
+    async fn find_subscription(
+        &self,
+        newsletter_id: Uuid,
+        email: String,
+    ) -> Result<Option<api_models::Subscription>, DomainError> {
+        self.run(move |conn| {
+            let sub: Option<Subscription> = subscriptions::table
+                .filter(subscriptions::newsletter_id.eq(newsletter_id))
+                .filter(lower(subscriptions::email).eq(lower(email)))
+                .select(Subscription::as_select())
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(sub.map(api_models::Subscription::from))
+        })
+        .await
+    }
+
+    async fn get_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError> {
+        self.run(move |conn| {
+            let sub: Option<Subscription> = subscriptions::table
+                .find(id)
+                .select(Subscription::as_select())
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            match sub {
+                None => Err(DomainError::NotFound(format!(
+                    "subscription not found for id = {}",
+                    id
+                ))),
+                Some(s) => Ok(api_models::Subscription {
+                    email: Some(s.email.clone()),
+                    ..api_models::Subscription::from(s)
+                }),
+            }
+        })
+        .await
+    }
+
+    async fn remove_subscription(&self, id: Uuid) -> Result<api_models::Subscription, DomainError> {
+        self.run(move |conn| {
+            let removed: Option<Subscription> = diesel::delete(subscriptions::table.find(id))
+                .returning(Subscription::as_returning())
+                .get_result(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            match removed {
+                None => Err(DomainError::NotFound(format!(
+                    "subscription not found for id = {}",
+                    id
+                ))),
+                Some(s) => Ok(api_models::Subscription {
+                    email: Some(s.email.clone()),
+                    ..api_models::Subscription::from(s)
+                }),
+            }
+        })
+        .await
+    }
+
+    async fn create_confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        issued_at: time::SystemTime,
+        expires_at: time::SystemTime,
+    ) -> Result<String, DomainError> {
+        let token = SubscriptionToken {
+            token: Uuid::new_v4().simple().to_string(),
+            subscription_id,
+            created_at: issued_at.into(),
+            expires_at: expires_at.into(),
+        };
+        self.run(move |conn| {
+            diesel::insert_into(subscription_tokens::table)
+                .values(&token)
+                .execute(conn)
+                .map(|_| token.token)
+                .map_err(|err| {
+                    DomainError::Internal(format!(
+                        "failed to store confirmation token (Error = {})",
+                        err
+                    ))
+                })
+        })
+        .await
+    }
+
+    async fn confirmation_token(
+        &self,
+        subscription_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<Option<String>, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            subscription_tokens::table
+                .filter(subscription_tokens::subscription_id.eq(subscription_id))
+                .filter(subscription_tokens::expires_at.gt(now))
+                .order(subscription_tokens::created_at.desc())
+                .select(subscription_tokens::token)
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))
+        })
+        .await
+    }
+
+    async fn confirm_subscription(
+        &self,
+        token: String,
+        confirmed_at: time::SystemTime,
+    ) -> Result<api_models::Subscription, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = confirmed_at.into();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                // deleting the token up front makes it single-use even under concurrent confirms
+                let consumed: Option<SubscriptionToken> = diesel::delete(
+                    subscription_tokens::table
+                        .filter(subscription_tokens::token.eq(&token))
+                        .filter(subscription_tokens::expires_at.gt(now)),
+                )
+                .returning(SubscriptionToken::as_returning())
+                .get_result(conn)
+                .optional()?;
+
+                let Some(consumed) = consumed else {
+                    return Ok(Err(DomainError::NotFound(
+                        "confirmation token is invalid or has expired".to_string(),
+                    )));
+                };
+
+                let sub: Subscription =
+                    diesel::update(subscriptions::table.find(consumed.subscription_id))
+                        .set(
+                            subscriptions::status
+                                .eq(api_models::SubscriptionStatus::Confirmed.as_str()),
+                        )
+                        .returning(Subscription::as_returning())
+                        .get_result(conn)?;
+
+                Ok(Ok(api_models::Subscription::from(sub)))
+            })
+            .map_err(|err: diesel::result::Error| {
+                DomainError::Internal(format!("failed to confirm subscription (Error = {})", err))
+            })?
+        })
+        .await
+    }
+
+    async fn remove_expired_confirmations(
+        &self,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let removed = diesel::delete(
+                    subscriptions::table
+                        .filter(
+                            subscriptions::status
+                                .eq(api_models::SubscriptionStatus::PendingConfirmation.as_str()),
+                        )
+                        .filter(diesel::dsl::exists(
+                            subscription_tokens::table
+                                .filter(subscription_tokens::subscription_id.eq(subscriptions::id))
+                                .filter(subscription_tokens::expires_at.le(now)),
+                        ))
+                        .filter(diesel::dsl::not(diesel::dsl::exists(
+                            subscription_tokens::table
+                                .filter(subscription_tokens::subscription_id.eq(subscriptions::id))
+                                .filter(subscription_tokens::expires_at.gt(now)),
+                        ))),
+                )
+                .execute(conn)?;
 
-        let res = diesel::insert_into(subscriptions::table)
-            .values(&subscription)
-            .execute(pool)
-            .map(|_| subscription)
-            .map_err(|err| {
+                diesel::delete(
+                    subscription_tokens::table.filter(subscription_tokens::expires_at.le(now)),
+                )
+                .execute(conn)?;
+
+                Ok(removed)
+            })
+            .map_err(|err: diesel::result::Error| {
                 DomainError::Internal(format!(
-                    "failed to store new subscription (Error = {})",
+                    "failed to remove expired confirmations (Error = {})",
                     err
                 ))
-            });
-
-        match res {
-            Ok(sub) => Ok(api_models::Subscription {
-                email: None,
-                subscription_id: sub.id.to_string(),
-                subscription_name: sub.name,
-                subscribe_since: sub.subscribed_at,
-            }),
-            Err(err) => Err(err),
-        }
+            })
+        })
+        .await
+    }
+
+    async fn list_subscriptions(
+        &self,
+        filter: &SubscriptionFilter,
+        after: Option<SubscriptionCursor>,
+        limit: usize,
+    ) -> Result<Vec<api_models::Subscription>, DomainError> {
+        let filter = filter.clone();
+        self.run(move |conn| {
+            let mut query = filter_subscriptions(
+                subscriptions::table
+                    .select(Subscription::as_select())
+                    .into_boxed(),
+                &filter,
+            );
+            if let Some(after) = after {
+                query = query.filter(
+                    subscriptions::subscribed_at.gt(after.subscribed_at).or(
+                        subscriptions::subscribed_at
+                            .eq(after.subscribed_at)
+                            .and(subscriptions::id.gt(after.id)),
+                    ),
+                );
+            }
+            let subs: Vec<Subscription> = query
+                .order((subscriptions::subscribed_at.asc(), subscriptions::id.asc()))
+                .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(subs
+                .into_iter()
+                .map(|sub| api_models::Subscription {
+                    email: Some(sub.email.clone()),
+                    ..api_models::Subscription::from(sub)
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn count_subscriptions(&self, filter: &SubscriptionFilter) -> Result<u64, DomainError> {
+        let filter = filter.clone();
+        self.run(move |conn| {
+            let total: i64 =
+                filter_subscriptions(subscriptions::table.count().into_boxed(), &filter)
+                    .get_result(conn)
+                    .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+            Ok(total.max(0) as u64)
+        })
+        .await
     }
+}
 
-    fn get_subscriptions(&mut self, email: String) -> Vec<api_models::Subscription> {
-        let pool = self.pool.get();
-        if pool.is_err() {
-            return Vec::new();
+fn newsletter_write_error(name: &str, err: diesel::result::Error) -> DomainError {
+    match err {
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            DomainError::Conflict(format!("a newsletter named {} already exists", name))
         }
-        let mut pool = pool.unwrap();
-        let subs: Vec<Subscription> = subscriptions::table
-            .filter(subscriptions::email.eq(email))
-            .select(Subscription::as_select())
-            .load(&mut pool)
-            .unwrap_or_else(|_| Vec::new());
-
-        subs.into_iter()
-            .map(|sub| api_models::Subscription {
-                email: None,
-                subscription_id: sub.id.to_string(),
-                subscription_name: sub.name,
-                subscribe_since: sub.subscribed_at,
+        err => DomainError::Internal(format!("failed to store newsletter (Error = {})", err)),
+    }
+}
+
+#[async_trait]
+impl NewsletterRepository for Repository {
+    async fn add_newsletter(
+        &self,
+        name: String,
+        description: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Newsletter, DomainError> {
+        let newsletter = Newsletter {
+            id: Uuid::new_v4(),
+            name,
+            description,
+            created_at: created_at.into(),
+        };
+        self.run(move |conn| {
+            diesel::insert_into(newsletters::table)
+                .values(&newsletter)
+                .execute(conn)
+                .map_err(|err| newsletter_write_error(&newsletter.name, err))?;
+
+            Ok(api_models::Newsletter::from(newsletter))
+        })
+        .await
+    }
+
+    async fn get_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError> {
+        self.run(move |conn| {
+            let newsletter: Option<Newsletter> = newsletters::table
+                .find(id)
+                .select(Newsletter::as_select())
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            newsletter.map(api_models::Newsletter::from).ok_or_else(|| {
+                DomainError::NotFound(format!("newsletter not found for id = {}", id))
             })
-            .collect()
-    }
-
-    fn remove_subscription(&mut self, id: Uuid) -> Result<api_models::Subscription, DomainError> {
-        let pool = self.pool.get();
-        match pool {
-            Ok(p) => {
-                let mut pool = p;
-                let subscription = Subscription {
-                    id,
-                    email: "".to_string(),
-                    name: "()".to_string(),
-                    subscribed_at: time::SystemTime::now().into(),
-                };
-                let res: Result<Subscription, DomainError> =
-                    diesel::delete(subscriptions::table.find(id))
-                        .execute(&mut pool)
-                        .map(|rows_affected| {
-                            if rows_affected == 0 {
-                                Err(DomainError::NotFound(format!(
-                                    "subscription not found for id = {}",
-                                    id
-                                )))
-                            } else {
-                                Ok(subscription.clone())
-                            }
-                        })
-                        .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+        })
+        .await
+    }
+
+    async fn get_newsletters(&self) -> Result<Vec<api_models::Newsletter>, DomainError> {
+        self.run(move |conn| {
+            let newsletters: Vec<Newsletter> = newsletters::table
+                .order(newsletters::name.asc())
+                .select(Newsletter::as_select())
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(newsletters
+                .into_iter()
+                .map(api_models::Newsletter::from)
+                .collect())
+        })
+        .await
+    }
+
+    async fn update_newsletter(
+        &self,
+        id: Uuid,
+        name: String,
+        description: String,
+    ) -> Result<api_models::Newsletter, DomainError> {
+        self.run(move |conn| {
+            let updated: Option<Newsletter> = diesel::update(newsletters::table.find(id))
+                .set((
+                    newsletters::name.eq(&name),
+                    newsletters::description.eq(description),
+                ))
+                .returning(Newsletter::as_returning())
+                .get_result(conn)
+                .optional()
+                .map_err(|err| newsletter_write_error(&name, err))?;
+
+            updated.map(api_models::Newsletter::from).ok_or_else(|| {
+                DomainError::NotFound(format!("newsletter not found for id = {}", id))
+            })
+        })
+        .await
+    }
+
+    async fn remove_newsletter(&self, id: Uuid) -> Result<api_models::Newsletter, DomainError> {
+        self.run(move |conn| {
+            let removed: Option<Newsletter> = diesel::delete(newsletters::table.find(id))
+                .returning(Newsletter::as_returning())
+                .get_result(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            removed.map(api_models::Newsletter::from).ok_or_else(|| {
+                DomainError::NotFound(format!("newsletter not found for id = {}", id))
+            })
+        })
+        .await
+    }
+}
+
+const DELIVERY_PENDING: &str = "pending";
+const DELIVERY_SENT: &str = "sent";
+const DELIVERY_FAILED: &str = "failed";
+
+#[async_trait]
+impl IssueRepository for Repository {
+    async fn add_issue(
+        &self,
+        newsletter_id: Uuid,
+        title: String,
+        text_body: String,
+        html_body: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::Issue, DomainError> {
+        let issue = Issue {
+            id: Uuid::new_v4(),
+            newsletter_id,
+            title,
+            text_body,
+            html_body,
+            created_at: created_at.into(),
+            published_at: None,
+        };
+        self.run(move |conn| {
+            diesel::insert_into(issues::table)
+                .values(&issue)
+                .execute(conn)
+                .map_err(|err| match err {
+                    diesel::result::Error::DatabaseError(
+                        DatabaseErrorKind::ForeignKeyViolation,
+                        _,
+                    ) => DomainError::NotFound(format!(
+                        "newsletter not found for id = {}",
+                        newsletter_id
+                    )),
+                    err => {
+                        DomainError::Internal(format!("failed to store issue (Error = {})", err))
+                    }
+                })?;
+
+            Ok(api_models::Issue::from(issue))
+        })
+        .await
+    }
+
+    async fn get_issue(
+        &self,
+        newsletter_id: Uuid,
+        issue_id: Uuid,
+    ) -> Result<api_models::Issue, DomainError> {
+        self.run(move |conn| {
+            let issue: Option<Issue> = issues::table
+                .filter(issues::id.eq(issue_id))
+                .filter(issues::newsletter_id.eq(newsletter_id))
+                .select(Issue::as_select())
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            issue.map(api_models::Issue::from).ok_or_else(|| {
+                DomainError::NotFound(format!(
+                    "issue {} not found for newsletter {}",
+                    issue_id, newsletter_id
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn enqueue_deliveries(
+        &self,
+        issue_id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<usize, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let newsletter_id: Uuid = issues::table
+                    .find(issue_id)
+                    .select(issues::newsletter_id)
+                    .first(conn)?;
 
-                match res {
-                    Err(err) => Err(err),
-                    Ok(s) => Ok(api_models::Subscription {
-                        email: s.email.clone().into(),
-                        subscription_id: s.id.to_string(),
-                        subscription_name: s.name,
-                        subscribe_since: s.subscribed_at,
-                    }),
+                let recipients = subscriptions::table
+                    .filter(subscriptions::newsletter_id.eq(newsletter_id))
+                    .filter(
+                        subscriptions::status
+                            .eq(api_models::SubscriptionStatus::Confirmed.as_str()),
+                    )
+                    .select((
+                        issue_id.into_sql::<diesel::sql_types::Uuid>(),
+                        subscriptions::id,
+                        DELIVERY_PENDING.into_sql::<diesel::sql_types::Text>(),
+                        now.into_sql::<diesel::sql_types::Timestamptz>(),
+                    ));
+                let added = diesel::insert_into(issue_deliveries::table)
+                    .values(recipients)
+                    .into_columns((
+                        issue_deliveries::issue_id,
+                        issue_deliveries::subscription_id,
+                        issue_deliveries::status,
+                        issue_deliveries::updated_at,
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                diesel::update(issues::table.find(issue_id))
+                    .filter(issues::published_at.is_null())
+                    .set(issues::published_at.eq(now))
+                    .execute(conn)?;
+
+                Ok(added)
+            })
+            .map_err(|err: diesel::result::Error| match err {
+                diesel::result::Error::NotFound => {
+                    DomainError::NotFound(format!("issue not found for id = {}", issue_id))
                 }
+                err => DomainError::Internal(format!(
+                    "failed to enqueue issue deliveries (Error = {})",
+                    err
+                )),
+            })
+        })
+        .await
+    }
+
+    async fn deliver_pending(
+        &self,
+        issue_id: Uuid,
+        mut deliver: Deliver,
+    ) -> Result<(), DomainError> {
+        self.run(move |conn| {
+            let candidates: Vec<Uuid> = issue_deliveries::table
+                .filter(issue_deliveries::issue_id.eq(issue_id))
+                .filter(issue_deliveries::status.ne(DELIVERY_SENT))
+                .select(issue_deliveries::subscription_id)
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            for subscription_id in candidates {
+                conn.transaction(|conn| {
+                    // rows locked by another publisher, or sent since we listed them, are skipped
+                    let delivery: Option<IssueDelivery> = issue_deliveries::table
+                        .find((issue_id, subscription_id))
+                        .filter(issue_deliveries::status.ne(DELIVERY_SENT))
+                        .select(IssueDelivery::as_select())
+                        .for_update()
+                        .skip_locked()
+                        .first(conn)
+                        .optional()?;
+                    let Some(delivery) = delivery else {
+                        return Ok(());
+                    };
+
+                    let email: String = subscriptions::table
+                        .find(subscription_id)
+                        .select(subscriptions::email)
+                        .first(conn)?;
+                    let recipient = Recipient {
+                        subscription_id,
+                        email,
+                    };
+                    let (status, last_error) = match deliver(&recipient) {
+                        Ok(_) => (DELIVERY_SENT, None),
+                        Err(err) => (DELIVERY_FAILED, Some(err.to_string())),
+                    };
+
+                    diesel::update(issue_deliveries::table.find((issue_id, subscription_id)))
+                        .set((
+                            issue_deliveries::status.eq(status),
+                            issue_deliveries::attempts.eq(delivery.attempts + 1),
+                            issue_deliveries::last_error.eq(last_error),
+                            issue_deliveries::updated_at.eq(chrono::DateTime::<chrono::Utc>::from(
+                                time::SystemTime::now(),
+                            )),
+                        ))
+                        .execute(conn)
+                        .map(|_| ())
+                })
+                .map_err(|err: diesel::result::Error| {
+                    DomainError::Internal(format!("failed to deliver issue (Error = {})", err))
+                })?;
             }
-            Err(err) => Err(DomainError::Internal(format!(
-                "Database error. Failed to get connection `(Err={})`",
-                err
-            ))),
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delivery_summary(
+        &self,
+        issue_id: Uuid,
+    ) -> Result<api_models::DeliverySummary, DomainError> {
+        self.run(move |conn| {
+            let counts: Vec<(String, i64)> = issue_deliveries::table
+                .filter(issue_deliveries::issue_id.eq(issue_id))
+                .group_by(issue_deliveries::status)
+                .select((issue_deliveries::status, diesel::dsl::count_star()))
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            let mut summary = api_models::DeliverySummary::default();
+            for (status, count) in counts {
+                let count = count as u64;
+                match status.as_str() {
+                    DELIVERY_SENT => summary.sent = count,
+                    DELIVERY_FAILED => summary.failed = count,
+                    _ => summary.pending += count,
+                }
+            }
+            Ok(summary)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl JobRepository for Repository {
+    async fn enqueue_job(
+        &self,
+        job: &domain_jobs::Job,
+        max_attempts: i32,
+        run_at: time::SystemTime,
+    ) -> Result<Uuid, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = time::SystemTime::now().into();
+        let payload = serde_json::to_value(job)
+            .map_err(|err| DomainError::Internal(format!("failed to encode job: {}", err)))?;
+        let row = Job {
+            id: Uuid::new_v4(),
+            kind: job.kind().to_string(),
+            payload,
+            status: api_models::JobStatus::Queued.as_str().to_string(),
+            attempts: 0,
+            max_attempts,
+            run_at: run_at.into(),
+            locked_until: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.run(move |conn| {
+            diesel::insert_into(jobs::table)
+                .values(&row)
+                .execute(conn)
+                .map(|_| row.id)
+                .map_err(|err| {
+                    DomainError::Internal(format!("failed to enqueue job (Error = {})", err))
+                })
+        })
+        .await
+    }
+
+    async fn claim_job(
+        &self,
+        now: time::SystemTime,
+        lease: time::Duration,
+    ) -> Result<Option<ClaimedJob>, DomainError> {
+        let locked_until: chrono::DateTime<chrono::Utc> = (now + lease).into();
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            let claimed: Option<Job> = conn
+                .transaction(|conn| {
+                    let due: Option<Job> = jobs::table
+                        .filter(
+                            jobs::status
+                                .eq(api_models::JobStatus::Queued.as_str())
+                                .and(jobs::run_at.le(now))
+                                .or(jobs::status
+                                    .eq(api_models::JobStatus::Running.as_str())
+                                    .and(jobs::locked_until.lt(now))),
+                        )
+                        .order(jobs::run_at.asc())
+                        .select(Job::as_select())
+                        .for_update()
+                        .skip_locked()
+                        .first(conn)
+                        .optional()?;
+                    let Some(due) = due else {
+                        return Ok(None);
+                    };
+
+                    diesel::update(jobs::table.find(due.id))
+                        .set((
+                            jobs::status.eq(api_models::JobStatus::Running.as_str()),
+                            jobs::attempts.eq(due.attempts + 1),
+                            jobs::locked_until.eq(locked_until),
+                            jobs::updated_at.eq(now),
+                        ))
+                        .returning(Job::as_returning())
+                        .get_result(conn)
+                        .map(Some)
+                })
+                .map_err(|err: diesel::result::Error| {
+                    DomainError::Internal(format!("failed to claim job (Error = {})", err))
+                })?;
+
+            let Some(claimed) = claimed else {
+                return Ok(None);
+            };
+            match serde_json::from_value::<domain_jobs::Job>(claimed.payload.clone()) {
+                Ok(job) => Ok(Some(ClaimedJob {
+                    id: claimed.id,
+                    job,
+                    attempts: claimed.attempts,
+                    max_attempts: claimed.max_attempts,
+                })),
+                Err(err) => {
+                    // a payload no worker understands will never succeed, retrying is pointless
+                    let error = format!("failed to decode job payload: {}", err);
+                    diesel::update(jobs::table.find(claimed.id))
+                        .set((
+                            jobs::status.eq(api_models::JobStatus::Dead.as_str()),
+                            jobs::locked_until.eq(None::<chrono::DateTime<chrono::Utc>>),
+                            jobs::last_error.eq(&error),
+                            jobs::updated_at.eq(now),
+                        ))
+                        .execute(conn)
+                        .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+                    Err(DomainError::Internal(error))
+                }
+            }
+        })
+        .await
+    }
+
+    async fn complete_job(&self, id: Uuid, now: time::SystemTime) -> Result<(), DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            diesel::update(jobs::table.find(id))
+                .set((
+                    jobs::status.eq(api_models::JobStatus::Succeeded.as_str()),
+                    jobs::locked_until.eq(None::<chrono::DateTime<chrono::Utc>>),
+                    jobs::last_error.eq(None::<String>),
+                    jobs::updated_at.eq(now),
+                ))
+                .execute(conn)
+                .map(|_| ())
+                .map_err(|err| {
+                    DomainError::Internal(format!("failed to complete job (Error = {})", err))
+                })
+        })
+        .await
+    }
+
+    async fn fail_job(
+        &self,
+        id: Uuid,
+        error: String,
+        retry_at: time::SystemTime,
+        now: time::SystemTime,
+    ) -> Result<api_models::JobStatus, DomainError> {
+        let retry_at: chrono::DateTime<chrono::Utc> = retry_at.into();
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            let status: String = diesel::update(jobs::table.find(id))
+                .set((
+                    jobs::status.eq(diesel::dsl::case_when(
+                        jobs::attempts.ge(jobs::max_attempts),
+                        api_models::JobStatus::Dead
+                            .as_str()
+                            .into_sql::<diesel::sql_types::Text>(),
+                    )
+                    .otherwise(
+                        api_models::JobStatus::Queued
+                            .as_str()
+                            .into_sql::<diesel::sql_types::Text>(),
+                    )),
+                    jobs::run_at.eq(retry_at),
+                    jobs::locked_until.eq(None::<chrono::DateTime<chrono::Utc>>),
+                    jobs::last_error.eq(error),
+                    jobs::updated_at.eq(now),
+                ))
+                .returning(jobs::status)
+                .get_result(conn)
+                .map_err(|err| match err {
+                    diesel::result::Error::NotFound => {
+                        DomainError::NotFound(format!("job not found for id = {}", id))
+                    }
+                    err => DomainError::Internal(format!("failed to fail job (Error = {})", err)),
+                })?;
+
+            Ok(api_models::JobStatus::from_str(status.as_str()).unwrap_or_default())
+        })
+        .await
+    }
+
+    async fn get_jobs(
+        &self,
+        status: api_models::JobStatus,
+    ) -> Result<Vec<api_models::Job>, DomainError> {
+        self.run(move |conn| {
+            let jobs: Vec<Job> = jobs::table
+                .filter(jobs::status.eq(status.as_str()))
+                .order(jobs::updated_at.desc())
+                .select(Job::as_select())
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(jobs.into_iter().map(api_models::Job::from).collect())
+        })
+        .await
+    }
+
+    async fn requeue_job(
+        &self,
+        id: Uuid,
+        now: time::SystemTime,
+    ) -> Result<api_models::Job, DomainError> {
+        let now: chrono::DateTime<chrono::Utc> = now.into();
+        self.run(move |conn| {
+            let requeued: Option<Job> = diesel::update(
+                jobs::table
+                    .find(id)
+                    .filter(jobs::status.eq(api_models::JobStatus::Dead.as_str())),
+            )
+            .set((
+                jobs::status.eq(api_models::JobStatus::Queued.as_str()),
+                jobs::attempts.eq(0),
+                jobs::run_at.eq(now),
+                jobs::updated_at.eq(now),
+            ))
+            .returning(Job::as_returning())
+            .get_result(conn)
+            .optional()
+            .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            requeued
+                .map(api_models::Job::from)
+                .ok_or_else(|| DomainError::NotFound(format!("dead job not found for id = {}", id)))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl HealthRepository for Repository {
+    async fn database_health(&self) -> DatabaseHealth {
+        let pool = self.pool_statistics();
+        let timeout = self.health_check_timeout;
+        let repo = self.clone();
+        let started = time::Instant::now();
+        let probe = tokio::task::spawn_blocking(move || {
+            let mut conn = repo
+                .pool
+                .get_timeout(timeout)
+                .map_err(|err| format!("failed to get a connection ({})", err))?;
+            diesel::sql_query("SELECT 1")
+                .execute(&mut conn)
+                .map_err(|err| format!("query failed ({})", err))?;
+            conn.pending_migrations(MIGRATIONS)
+                .map(|pending| {
+                    pending
+                        .iter()
+                        .map(|m| m.name().to_string())
+                        .collect::<Vec<String>>()
+                })
+                .map_err(|err| format!("failed to read migrations ({})", err))
+        });
+
+        let (error, pending_migrations) = match tokio::time::timeout(timeout, probe).await {
+            Ok(Ok(Ok(pending))) => (None, Some(pending)),
+            Ok(Ok(Err(err))) => (Some(err), None),
+            Ok(Err(err)) => (Some(format!("probe task failed ({})", err)), None),
+            Err(_) => (Some(format!("timed out after {:?}", timeout)), None),
+        };
+        DatabaseHealth {
+            error,
+            latency: started.elapsed(),
+            pool,
+            pending_migrations,
         }
     }
+
+    fn pool_statistics(&self) -> PoolStatistics {
+        let state = self.pool.state();
+        PoolStatistics {
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            max_size: self.pool.max_size(),
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for Repository {
+    async fn add_api_key(
+        &self,
+        name: String,
+        role: api_models::ApiKeyRole,
+        key_hash: String,
+        created_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError> {
+        let key = ApiKey {
+            id: Uuid::new_v4(),
+            name,
+            key_hash,
+            role: role.as_str().to_string(),
+            created_at: created_at.into(),
+            revoked_at: None,
+        };
+        self.run(move |conn| {
+            diesel::insert_into(api_keys::table)
+                .values(&key)
+                .execute(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(api_models::ApiKey::from(key))
+        })
+        .await
+    }
+
+    async fn find_api_key(
+        &self,
+        key_hash: String,
+    ) -> Result<Option<api_models::ApiKey>, DomainError> {
+        self.run(move |conn| {
+            let key: Option<ApiKey> = api_keys::table
+                .filter(api_keys::key_hash.eq(key_hash))
+                .filter(api_keys::revoked_at.is_null())
+                .select(ApiKey::as_select())
+                .first(conn)
+                .optional()
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(key.map(api_models::ApiKey::from))
+        })
+        .await
+    }
+
+    async fn get_api_keys(&self) -> Result<Vec<api_models::ApiKey>, DomainError> {
+        self.run(move |conn| {
+            let keys: Vec<ApiKey> = api_keys::table
+                .select(ApiKey::as_select())
+                .order(api_keys::created_at.asc())
+                .load(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+
+            Ok(keys.into_iter().map(api_models::ApiKey::from).collect())
+        })
+        .await
+    }
+
+    async fn revoke_api_key(
+        &self,
+        id: Uuid,
+        revoked_at: time::SystemTime,
+    ) -> Result<api_models::ApiKey, DomainError> {
+        let revoked_at: chrono::DateTime<chrono::Utc> = revoked_at.into();
+        self.run(move |conn| {
+            let revoked: Option<ApiKey> = diesel::update(
+                api_keys::table
+                    .find(id)
+                    .filter(api_keys::revoked_at.is_null()),
+            )
+            .set(api_keys::revoked_at.eq(revoked_at))
+            .returning(ApiKey::as_returning())
+            .get_result(conn)
+            .optional()
+            .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+            let key = match revoked {
+                Some(key) => Some(key),
+                None => api_keys::table
+                    .find(id)
+                    .select(ApiKey::as_select())
+                    .first(conn)
+                    .optional()
+                    .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?,
+            };
+
+            key.map(api_models::ApiKey::from)
+                .ok_or_else(|| DomainError::NotFound(format!("api key not found for id = {}", id)))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for Repository {
+    async fn reserve_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        now: time::SystemTime,
+        ttl: time::Duration,
+        in_progress_timeout: time::Duration,
+    ) -> Result<Reservation, DomainError> {
+        let stale_before: chrono::DateTime<chrono::Utc> = now
+            .checked_sub(in_progress_timeout)
+            .unwrap_or(time::UNIX_EPOCH)
+            .into();
+        let record = IdempotencyRecord {
+            caller: request.caller,
+            key: request.key,
+            request_hash: request.request_hash,
+            response_status: None,
+            response_headers: None,
+            response_body: None,
+            created_at: now.into(),
+            expires_at: (now + ttl).into(),
+        };
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                diesel::delete(
+                    idempotency_keys::table
+                        .filter(idempotency_keys::expires_at.le(record.created_at)),
+                )
+                .execute(conn)?;
+                let inserted = diesel::insert_into(idempotency_keys::table)
+                    .values(&record)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+                if inserted == 1 {
+                    return Ok(Reservation::Started);
+                }
+
+                let existing: Option<IdempotencyRecord> = idempotency_keys::table
+                    .find((&record.caller, &record.key))
+                    .select(IdempotencyRecord::as_select())
+                    .for_update()
+                    .first(conn)
+                    .optional()?;
+                // removed by its holder in the meantime, so it is still being worked on
+                let Some(existing) = existing else {
+                    return Ok(Reservation::InProgress);
+                };
+                if existing.request_hash != record.request_hash {
+                    return Ok(Reservation::PayloadMismatch);
+                }
+                if let Some(response) = existing.response() {
+                    return Ok(Reservation::Completed(response));
+                }
+                if existing.created_at > stale_before {
+                    return Ok(Reservation::InProgress);
+                }
+                diesel::update(idempotency_keys::table.find((&record.caller, &record.key)))
+                    .set((
+                        idempotency_keys::created_at.eq(record.created_at),
+                        idempotency_keys::expires_at.eq(record.expires_at),
+                    ))
+                    .execute(conn)?;
+                Ok(Reservation::Started)
+            })
+            .map_err(|err: diesel::result::Error| {
+                DomainError::Internal(format!(
+                    "failed to reserve idempotency key (Error = {})",
+                    err
+                ))
+            })
+        })
+        .await
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        request: IdempotentRequest,
+        response: StoredResponse,
+    ) -> Result<(), DomainError> {
+        let status = i16::try_from(response.status)
+            .map_err(|_| DomainError::Internal(format!("invalid status {}", response.status)))?;
+        let headers = serde_json::to_value(&response.headers)
+            .map_err(|err| DomainError::Internal(format!("failed to encode headers: {}", err)))?;
+        self.run(move |conn| {
+            diesel::update(idempotency_keys::table.find((request.caller, request.key)))
+                .set((
+                    idempotency_keys::response_status.eq(status),
+                    idempotency_keys::response_headers.eq(headers),
+                    idempotency_keys::response_body.eq(response.body),
+                ))
+                .execute(conn)
+                .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn release_idempotency_key(&self, request: IdempotentRequest) -> Result<(), DomainError> {
+        self.run(move |conn| {
+            diesel::delete(
+                idempotency_keys::table
+                    .find((request.caller, request.key))
+                    .filter(idempotency_keys::response_status.is_null()),
+            )
+            .execute(conn)
+            .map_err(|err| DomainError::Internal(format!("Database error: {}", err)))?;
+            Ok(())
+        })
+        .await
+    }
 }
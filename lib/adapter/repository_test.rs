@@ -2,11 +2,21 @@
 mod test {
 
     use std::str::FromStr;
-    use std::time;
+    use std::sync::{Arc, Mutex};
+    use std::time::{self, Duration};
 
-    use crate::adapter::repository::SubscriptionRepository;
+    use crate::adapter::repository::{
+        ApiKeyRepository, IdempotencyRepository, IssueRepository, JobRepository,
+        NewsletterRepository, Recipient, SubscriptionCursor, SubscriptionFilter,
+        SubscriptionRepository,
+    };
     use crate::adapter::{configuration, repository::Repository};
     use crate::domain::errors::DomainError;
+    use crate::domain::idempotency::{
+        Caller, IdempotencyKey, IdempotentRequest, Reservation, StoredResponse,
+    };
+    use crate::domain::jobs::Job;
+    use crate::model::models::{ApiKeyRole, JobStatus, SubscriptionStatus};
     use dotenvy::dotenv;
     use fake::{faker::internet::en::SafeEmail, Fake};
     use uuid::Uuid;
@@ -24,9 +34,46 @@ mod test {
         }
     }
 
+    /// fake address made unique, as tests share one database and the faker's pool is small
+    fn unique_email() -> String {
+        format!(
+            "{}.{}",
+            Uuid::new_v4().simple(),
+            SafeEmail().fake::<String>()
+        )
+    }
+
     fn get_db_configuration() -> configuration::DatabaseConfiguration {
         dotenv().ok();
-        configuration::DatabaseConfiguration::new()
+        configuration::Settings::load().unwrap().database
+    }
+
+    async fn create_confirmed_subscription(repo: &Repository, newsletter_id: Uuid) -> Uuid {
+        let now = time::SystemTime::now();
+        let fake_email: String = unique_email();
+        let sub = repo
+            .add_subscription(newsletter_id, "a".to_string(), fake_email, now)
+            .await
+            .unwrap();
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let token = repo
+            .create_confirmation_token(id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        repo.confirm_subscription(token, now).await.unwrap();
+        id
+    }
+
+    async fn create_newsletter(repo: &Repository) -> Uuid {
+        let newsletter = repo
+            .add_newsletter(
+                format!("newsletter-{}", Uuid::new_v4()),
+                "".to_string(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        Uuid::from_str(newsletter.newsletter_id.as_str()).unwrap()
     }
 
     #[tokio::test]
@@ -34,43 +81,58 @@ mod test {
         // arrange
         let cfg = get_db_configuration();
         let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
         const EMAIL: &str = "ydot19@github.com";
         // act
-        let result = ctx.repo.add_subscription(
-            "Ydot19".to_string(),
-            EMAIL.to_string(),
-            time::SystemTime::now(),
-        );
+        let result = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "Ydot19".to_string(),
+                EMAIL.to_string(),
+                time::SystemTime::now(),
+            )
+            .await;
         // assert
         assert!(result.is_ok());
         let res = result.unwrap();
-        assert_eq!("Ydot19".to_string(), res.subscription_name);
+        assert_eq!(Some("Ydot19".to_string()), res.subscriber_name);
     }
 
     #[tokio::test]
     async fn get_subscriptions() {
         // arrange
         let cfg = get_db_configuration();
-        let mut ctx = TestContext::new(cfg).await;
-        let fake_email: String = SafeEmail().fake();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let other_newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email: String = unique_email();
         let repo = ctx.repo.clone();
-        let first = repo.clone().add_subscription(
-            "a".to_string(),
-            fake_email.clone(),
-            time::SystemTime::now(),
-        );
+        let first = repo
+            .clone()
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                fake_email.clone(),
+                time::SystemTime::now(),
+            )
+            .await;
         assert!(first.is_ok());
         let first_subscription = first.unwrap();
 
-        let second = repo.clone().add_subscription(
-            "b".to_string(),
-            fake_email.clone(),
-            time::SystemTime::now(),
-        );
+        let second = repo
+            .clone()
+            .add_subscription(
+                other_newsletter_id,
+                "b".to_string(),
+                fake_email.clone(),
+                time::SystemTime::now(),
+            )
+            .await;
         assert!(second.is_ok());
         let second_subscription = second.unwrap();
         // ACT - 1
-        let res = ctx.repo.get_subscriptions(fake_email.clone());
+        let res = ctx.repo.get_subscriptions(fake_email.clone(), true).await;
 
         // assert
         println!("Length of Result: {}", res.len());
@@ -86,7 +148,7 @@ mod test {
             .any(|el| el.subscription_id == second_subscription.subscription_id));
         // ACT - 2
         let second_id = Uuid::from_str(second_subscription.subscription_id.as_str());
-        let result = ctx.repo.remove_subscription(second_id.unwrap());
+        let result = ctx.repo.remove_subscription(second_id.unwrap()).await;
 
         // assert
         assert!(result.is_ok());
@@ -94,16 +156,738 @@ mod test {
         assert_eq!(second_subscription.subscription_id, result.subscription_id)
     }
 
+    #[tokio::test]
+    async fn get_subscriptions_ignores_email_case() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email = unique_email();
+        let stored = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                fake_email.to_uppercase(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+
+        // act
+        let res = ctx.repo.get_subscriptions(fake_email, true).await;
+
+        // assert
+        assert_eq!(1, res.len());
+        assert_eq!(stored.subscription_id, res[0].subscription_id);
+    }
+
+    #[tokio::test]
+    async fn add_subscription_duplicate_email() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email: String = unique_email();
+        let first = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                fake_email.clone(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+
+        // act
+        let duplicate = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                fake_email.to_uppercase(),
+                time::SystemTime::now(),
+            )
+            .await;
+        let found = ctx
+            .repo
+            .find_subscription(newsletter_id, fake_email.to_uppercase())
+            .await;
+        let missing = ctx
+            .repo
+            .find_subscription(newsletter_id, "a@b.c".to_string())
+            .await;
+
+        // assert
+        assert!(matches!(duplicate.unwrap_err(), DomainError::Conflict(_)));
+        assert_eq!(
+            first.subscription_id,
+            found.unwrap().unwrap().subscription_id
+        );
+        assert!(missing.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn remove_subscriptions() {
         // arrange
         let cfg = get_db_configuration();
-        let mut ctx = TestContext::new(cfg).await;
+        let ctx = TestContext::new(cfg).await;
         let subscription_id = Uuid::new_v4();
         // act
-        let result = ctx.repo.remove_subscription(subscription_id);
+        let result = ctx.repo.remove_subscription(subscription_id).await;
         // assert
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), DomainError::NotFound(_)))
     }
+
+    #[tokio::test]
+    async fn confirm_subscription() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email: String = unique_email();
+        let now = time::SystemTime::now();
+        let sub = ctx
+            .repo
+            .add_subscription(newsletter_id, "a".to_string(), fake_email.clone(), now)
+            .await
+            .unwrap();
+        assert_eq!(SubscriptionStatus::PendingConfirmation, sub.status);
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let token = ctx
+            .repo
+            .create_confirmation_token(id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(ctx
+            .repo
+            .get_subscriptions(fake_email.clone(), false)
+            .await
+            .is_empty());
+
+        // act
+        let result = ctx.repo.confirm_subscription(token.clone(), now).await;
+
+        // assert
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(sub.subscription_id, result.subscription_id);
+        assert_eq!(SubscriptionStatus::Confirmed, result.status);
+        let confirmed = ctx.repo.get_subscriptions(fake_email.clone(), false).await;
+        assert_eq!(1, confirmed.len());
+
+        // tokens are single-use
+        let again = ctx.repo.confirm_subscription(token, now).await;
+        assert!(matches!(again.unwrap_err(), DomainError::NotFound(_)))
+    }
+
+    #[tokio::test]
+    async fn confirm_subscription_expired_token() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email: String = unique_email();
+        let issued_at = time::SystemTime::now() - Duration::from_secs(120);
+        let sub = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                fake_email.clone(),
+                issued_at,
+            )
+            .await
+            .unwrap();
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let token = ctx
+            .repo
+            .create_confirmation_token(id, issued_at, issued_at + Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // act
+        let result = ctx
+            .repo
+            .confirm_subscription(token, time::SystemTime::now())
+            .await;
+
+        // assert
+        assert!(matches!(result.unwrap_err(), DomainError::NotFound(_)))
+    }
+
+    #[tokio::test]
+    async fn confirmation_token_is_the_latest_unexpired_one() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let now = time::SystemTime::now();
+        let sub = ctx
+            .repo
+            .add_subscription(newsletter_id, "a".to_string(), unique_email(), now)
+            .await
+            .unwrap();
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let issue = |issued_at: time::SystemTime| {
+            ctx.repo
+                .create_confirmation_token(id, issued_at, issued_at + Duration::from_secs(60))
+        };
+        issue(now - Duration::from_secs(30)).await.unwrap();
+        let latest = issue(now).await.unwrap();
+
+        // act
+        let current = ctx.repo.confirmation_token(id, now).await;
+        let expired = ctx
+            .repo
+            .confirmation_token(id, now + Duration::from_secs(60))
+            .await;
+
+        // assert
+        assert_eq!(Some(latest), current.unwrap());
+        assert_eq!(None, expired.unwrap());
+    }
+
+    #[tokio::test]
+    async fn remove_expired_confirmations() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let other_newsletter_id = create_newsletter(&ctx.repo).await;
+        let fake_email: String = unique_email();
+        let now = time::SystemTime::now();
+        let issued_at = now - Duration::from_secs(120);
+        let expired = ctx
+            .repo
+            .add_subscription(
+                newsletter_id,
+                "expired".to_string(),
+                fake_email.clone(),
+                issued_at,
+            )
+            .await
+            .unwrap();
+        let expired_id = Uuid::from_str(expired.subscription_id.as_str()).unwrap();
+        ctx.repo
+            .create_confirmation_token(expired_id, issued_at, issued_at + Duration::from_secs(60))
+            .await
+            .unwrap();
+        let live = ctx
+            .repo
+            .add_subscription(
+                other_newsletter_id,
+                "live".to_string(),
+                fake_email.clone(),
+                now,
+            )
+            .await
+            .unwrap();
+        let live_id = Uuid::from_str(live.subscription_id.as_str()).unwrap();
+        ctx.repo
+            .create_confirmation_token(live_id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // act
+        let result = ctx.repo.remove_expired_confirmations(now).await;
+
+        // assert
+        assert!(result.is_ok());
+        let remaining = ctx.repo.get_subscriptions(fake_email, true).await;
+        assert_eq!(1, remaining.len());
+        assert_eq!(live.subscription_id, remaining[0].subscription_id);
+    }
+
+    #[tokio::test]
+    async fn list_subscriptions_pages_through_matches() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let mut confirmed = Vec::new();
+        for _ in 0..3 {
+            confirmed.push(create_confirmed_subscription(&ctx.repo, newsletter_id).await);
+        }
+        ctx.repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                unique_email(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        let filter = SubscriptionFilter {
+            newsletter_id: Some(newsletter_id),
+            status: Some(SubscriptionStatus::Confirmed),
+            ..Default::default()
+        };
+
+        // act
+        let first = ctx.repo.list_subscriptions(&filter, None, 2).await.unwrap();
+        let last = first.last().unwrap();
+        let after = SubscriptionCursor {
+            subscribed_at: last.subscribe_since,
+            id: Uuid::from_str(&last.subscription_id).unwrap(),
+        };
+        let second = ctx
+            .repo
+            .list_subscriptions(&filter, Some(after), 2)
+            .await
+            .unwrap();
+        let total = ctx.repo.count_subscriptions(&filter).await.unwrap();
+
+        // assert
+        let listed: Vec<Uuid> = first
+            .iter()
+            .chain(second.iter())
+            .map(|sub| Uuid::from_str(&sub.subscription_id).unwrap())
+            .collect();
+        assert_eq!(confirmed, listed);
+        assert_eq!(1, second.len());
+        assert!(first.iter().all(|sub| sub.email.is_some()));
+        assert_eq!(3, total);
+    }
+
+    #[tokio::test]
+    async fn add_subscription_unknown_newsletter() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let fake_email: String = unique_email();
+        // act
+        let result = ctx
+            .repo
+            .add_subscription(
+                Uuid::new_v4(),
+                "a".to_string(),
+                fake_email,
+                time::SystemTime::now(),
+            )
+            .await;
+        // assert
+        assert!(matches!(result.unwrap_err(), DomainError::NotFound(_)))
+    }
+
+    #[tokio::test]
+    async fn newsletter_crud() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let name = format!("newsletter-{}", Uuid::new_v4());
+
+        // act - create
+        let created = ctx
+            .repo
+            .add_newsletter(name.clone(), "daily".to_string(), time::SystemTime::now())
+            .await
+            .unwrap();
+        let id = Uuid::from_str(created.newsletter_id.as_str()).unwrap();
+
+        // assert
+        assert_eq!(name, created.name);
+        assert_eq!(name, ctx.repo.get_newsletter(id).await.unwrap().name);
+        assert!(ctx
+            .repo
+            .get_newsletters()
+            .await
+            .unwrap()
+            .iter()
+            .any(|n| n.newsletter_id == created.newsletter_id));
+
+        // act - duplicate name
+        let duplicate = ctx
+            .repo
+            .add_newsletter(name.clone(), "".to_string(), time::SystemTime::now())
+            .await;
+        assert!(matches!(duplicate.unwrap_err(), DomainError::Conflict(_)));
+
+        // act - update
+        let renamed = format!("{}-renamed", name);
+        let updated = ctx
+            .repo
+            .update_newsletter(id, renamed.clone(), "weekly".to_string())
+            .await
+            .unwrap();
+        assert_eq!(renamed, updated.name);
+        assert_eq!("weekly", updated.description);
+
+        // act - remove cascades to subscriptions
+        let fake_email: String = unique_email();
+        ctx.repo
+            .add_subscription(
+                id,
+                "a".to_string(),
+                fake_email.clone(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        let removed = ctx.repo.remove_newsletter(id).await.unwrap();
+        assert_eq!(created.newsletter_id, removed.newsletter_id);
+        assert!(matches!(
+            ctx.repo.get_newsletter(id).await.unwrap_err(),
+            DomainError::NotFound(_)
+        ));
+        assert!(ctx
+            .repo
+            .get_subscriptions(fake_email, true)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn newsletter_not_found() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let id = Uuid::new_v4();
+        // act
+        let get = ctx.repo.get_newsletter(id).await;
+        let update = ctx
+            .repo
+            .update_newsletter(id, "a".to_string(), "".to_string())
+            .await;
+        let remove = ctx.repo.remove_newsletter(id).await;
+        // assert
+        assert!(matches!(get.unwrap_err(), DomainError::NotFound(_)));
+        assert!(matches!(update.unwrap_err(), DomainError::NotFound(_)));
+        assert!(matches!(remove.unwrap_err(), DomainError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn publish_issue_resumes_without_resending() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        let first = create_confirmed_subscription(&ctx.repo, newsletter_id).await;
+        let second = create_confirmed_subscription(&ctx.repo, newsletter_id).await;
+        let pending: String = unique_email();
+        ctx.repo
+            .add_subscription(
+                newsletter_id,
+                "a".to_string(),
+                pending,
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        let issue = ctx
+            .repo
+            .add_issue(
+                newsletter_id,
+                "Issue #1".to_string(),
+                "text".to_string(),
+                "<p>html</p>".to_string(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        let issue_id = Uuid::from_str(issue.issue_id.as_str()).unwrap();
+
+        // act - first publish fails for one recipient
+        let added = ctx
+            .repo
+            .enqueue_deliveries(issue_id, time::SystemTime::now())
+            .await
+            .unwrap();
+        let attempted: Arc<Mutex<Vec<Recipient>>> = Arc::default();
+        let sink = attempted.clone();
+        ctx.repo
+            .deliver_pending(
+                issue_id,
+                Box::new(move |recipient| {
+                    sink.lock().unwrap().push(recipient.clone());
+                    if recipient.subscription_id == first {
+                        Err(DomainError::Internal("smtp down".to_string()))
+                    } else {
+                        Ok(())
+                    }
+                }),
+            )
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(2, added);
+        assert_eq!(2, attempted.lock().unwrap().len());
+        let summary = ctx.repo.delivery_summary(issue_id).await.unwrap();
+        assert_eq!(1, summary.sent);
+        assert_eq!(1, summary.failed);
+        assert!(ctx
+            .repo
+            .get_issue(newsletter_id, issue_id)
+            .await
+            .unwrap()
+            .published_at
+            .is_some());
+
+        // act - resume only retries what was not sent
+        let added = ctx
+            .repo
+            .enqueue_deliveries(issue_id, time::SystemTime::now())
+            .await
+            .unwrap();
+        let retried: Arc<Mutex<Vec<Uuid>>> = Arc::default();
+        let sink = retried.clone();
+        ctx.repo
+            .deliver_pending(
+                issue_id,
+                Box::new(move |recipient| {
+                    sink.lock().unwrap().push(recipient.subscription_id);
+                    Ok(())
+                }),
+            )
+            .await
+            .unwrap();
+        let retried = retried.lock().unwrap().clone();
+
+        // assert
+        assert_eq!(0, added);
+        assert_eq!(vec![first], retried);
+        assert!(!retried.contains(&second));
+        let summary = ctx.repo.delivery_summary(issue_id).await.unwrap();
+        assert_eq!(2, summary.sent);
+        assert_eq!(0, summary.failed);
+    }
+
+    #[tokio::test]
+    async fn issue_not_found() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let newsletter_id = create_newsletter(&ctx.repo).await;
+        // act
+        let add = ctx
+            .repo
+            .add_issue(
+                Uuid::new_v4(),
+                "a".to_string(),
+                "".to_string(),
+                "".to_string(),
+                time::SystemTime::now(),
+            )
+            .await;
+        let get = ctx.repo.get_issue(newsletter_id, Uuid::new_v4()).await;
+        let enqueue = ctx
+            .repo
+            .enqueue_deliveries(Uuid::new_v4(), time::SystemTime::now())
+            .await;
+        // assert
+        assert!(matches!(add.unwrap_err(), DomainError::NotFound(_)));
+        assert!(matches!(get.unwrap_err(), DomainError::NotFound(_)));
+        assert!(matches!(enqueue.unwrap_err(), DomainError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn job_lifecycle() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        // claims only see jobs due by `now`, so a point far in the past keeps this test
+        // away from jobs enqueued by anything else
+        let offset: u64 = (1_000_000..100_000_000).fake();
+        let base = time::UNIX_EPOCH + Duration::from_secs(offset);
+        let lease = Duration::from_secs(30);
+        let job = Job::SendConfirmation {
+            subscription_id: Uuid::new_v4(),
+        };
+        let id = ctx.repo.enqueue_job(&job, 2, base).await.unwrap();
+
+        // act - not due yet
+        let early = ctx
+            .repo
+            .claim_job(base - Duration::from_secs(1), lease)
+            .await;
+        // act - first attempt
+        let first = ctx.repo.claim_job(base, lease).await.unwrap().unwrap();
+        let leased = ctx.repo.claim_job(base, lease).await;
+        // act - lease expires and another worker takes over
+        let second = ctx
+            .repo
+            .claim_job(base + lease + Duration::from_secs(1), lease)
+            .await
+            .unwrap()
+            .unwrap();
+        let status = ctx
+            .repo
+            .fail_job(id, "smtp down".to_string(), base, base)
+            .await
+            .unwrap();
+
+        // assert
+        assert!(early.unwrap().is_none());
+        assert_eq!(id, first.id);
+        assert_eq!(job, first.job);
+        assert_eq!(1, first.attempts);
+        assert!(leased.unwrap().is_none());
+        assert_eq!(id, second.id);
+        assert_eq!(2, second.attempts);
+        assert_eq!(JobStatus::Dead, status);
+        let dead = ctx.repo.get_jobs(JobStatus::Dead).await.unwrap();
+        let dead = dead.iter().find(|j| j.job_id == id.to_string()).unwrap();
+        assert_eq!("send_confirmation", dead.kind);
+        assert_eq!(Some("smtp down".to_string()), dead.last_error);
+        assert!(ctx.repo.claim_job(base, lease).await.unwrap().is_none());
+
+        // act - requeue the dead job and fail it once with a backoff
+        let requeued = ctx.repo.requeue_job(id, base).await.unwrap();
+        let requeued_again = ctx.repo.requeue_job(id, base).await;
+        let third = ctx.repo.claim_job(base, lease).await.unwrap().unwrap();
+        let retry_at = base + Duration::from_secs(10);
+        let status = ctx
+            .repo
+            .fail_job(id, "smtp down".to_string(), retry_at, base)
+            .await
+            .unwrap();
+        let before_retry = ctx.repo.claim_job(base, lease).await;
+        let retried = ctx.repo.claim_job(retry_at, lease).await.unwrap().unwrap();
+        let completed = ctx.repo.complete_job(id, retry_at).await;
+
+        // assert
+        assert_eq!(JobStatus::Queued, requeued.status);
+        assert_eq!(0, requeued.attempts);
+        assert!(matches!(
+            requeued_again.unwrap_err(),
+            DomainError::NotFound(_)
+        ));
+        assert_eq!(1, third.attempts);
+        assert_eq!(JobStatus::Queued, status);
+        assert!(before_retry.unwrap().is_none());
+        assert_eq!(2, retried.attempts);
+        assert!(completed.is_ok());
+        assert!(ctx.repo.claim_job(retry_at, lease).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn api_key_lifecycle() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let key_hash = format!("hash-{}", Uuid::new_v4());
+        let now = time::SystemTime::now();
+        let key = ctx
+            .repo
+            .add_api_key(
+                "ci".to_string(),
+                ApiKeyRole::Publisher,
+                key_hash.clone(),
+                now,
+            )
+            .await
+            .unwrap();
+        let id = Uuid::from_str(key.api_key_id.as_str()).unwrap();
+
+        // act
+        let found = ctx.repo.find_api_key(key_hash.clone()).await.unwrap();
+        let revoked = ctx.repo.revoke_api_key(id, now).await.unwrap();
+        let revoked_again = ctx
+            .repo
+            .revoke_api_key(id, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        let found_after = ctx.repo.find_api_key(key_hash).await.unwrap();
+        let unknown = ctx.repo.revoke_api_key(Uuid::new_v4(), now).await;
+
+        // assert
+        assert_eq!(ApiKeyRole::Publisher, found.unwrap().role);
+        assert!(revoked.revoked_at.is_some());
+        assert_eq!(revoked.revoked_at, revoked_again.revoked_at);
+        assert!(found_after.is_none());
+        assert!(matches!(unknown, Err(DomainError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_lifecycle() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let key = IdempotencyKey::parse(&Uuid::new_v4().to_string()).unwrap();
+        let request = IdempotentRequest::new(
+            &key,
+            Caller::Authorized(b"Bearer k"),
+            "POST",
+            "/subscribe",
+            b"{}",
+        );
+        let other = IdempotentRequest::new(
+            &key,
+            Caller::Authorized(b"Bearer k"),
+            "POST",
+            "/subscribe",
+            b"[]",
+        );
+        let response = StoredResponse {
+            status: 201,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: b"{}".to_vec(),
+        };
+        let now = time::SystemTime::now();
+        let ttl = Duration::from_secs(3600);
+        let timeout = Duration::from_secs(60);
+        let reserve = |request: &IdempotentRequest, at: time::SystemTime| {
+            ctx.repo
+                .reserve_idempotency_key(request.clone(), at, ttl, timeout)
+        };
+
+        // act
+        let started = reserve(&request, now).await.unwrap();
+        let in_progress = reserve(&request, now).await.unwrap();
+        let taken_over = reserve(&request, now + timeout).await.unwrap();
+        ctx.repo
+            .complete_idempotency_key(request.clone(), response.clone())
+            .await
+            .unwrap();
+        let completed = reserve(&request, now + timeout).await.unwrap();
+        let mismatch = reserve(&other, now + timeout).await.unwrap();
+        let expired = reserve(&request, now + timeout + ttl).await.unwrap();
+
+        // assert
+        assert_eq!(Reservation::Started, started);
+        assert_eq!(Reservation::InProgress, in_progress);
+        assert_eq!(Reservation::Started, taken_over);
+        assert_eq!(Reservation::Completed(response), completed);
+        assert_eq!(Reservation::PayloadMismatch, mismatch);
+        assert_eq!(Reservation::Started, expired);
+    }
+
+    #[tokio::test]
+    async fn released_idempotency_key_can_be_reserved_again() {
+        // arrange
+        let cfg = get_db_configuration();
+        let ctx = TestContext::new(cfg).await;
+        let key = IdempotencyKey::parse(&Uuid::new_v4().to_string()).unwrap();
+        let request = IdempotentRequest::new(
+            &key,
+            Caller::Authorized(b"Bearer k"),
+            "DELETE",
+            "/subscribe",
+            b"",
+        );
+        let now = time::SystemTime::now();
+        let ttl = Duration::from_secs(3600);
+        let timeout = Duration::from_secs(60);
+        ctx.repo
+            .reserve_idempotency_key(request.clone(), now, ttl, timeout)
+            .await
+            .unwrap();
+
+        // act
+        ctx.repo
+            .release_idempotency_key(request.clone())
+            .await
+            .unwrap();
+        let reserved = ctx
+            .repo
+            .reserve_idempotency_key(request, now, ttl, timeout)
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(Reservation::Started, reserved);
+    }
 }
@@ -1,10 +1,110 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        name -> Text,
+        key_hash -> Text,
+        role -> Text,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (caller, key) {
+        caller -> Text,
+        key -> Text,
+        request_hash -> Text,
+        response_status -> Nullable<Int2>,
+        response_headers -> Nullable<Jsonb>,
+        response_body -> Nullable<Bytea>,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    issue_deliveries (issue_id, subscription_id) {
+        issue_id -> Uuid,
+        subscription_id -> Uuid,
+        status -> Text,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    issues (id) {
+        id -> Uuid,
+        newsletter_id -> Uuid,
+        title -> Text,
+        text_body -> Text,
+        html_body -> Text,
+        created_at -> Timestamptz,
+        published_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    jobs (id) {
+        id -> Uuid,
+        kind -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        attempts -> Int4,
+        max_attempts -> Int4,
+        run_at -> Timestamptz,
+        locked_until -> Nullable<Timestamptz>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    newsletters (id) {
+        id -> Uuid,
+        name -> Text,
+        description -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    subscription_tokens (token) {
+        token -> Text,
+        subscription_id -> Uuid,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     subscriptions (id) {
         id -> Uuid,
         email -> Text,
-        name -> Text,
         subscribed_at -> Timestamptz,
+        status -> Text,
+        newsletter_id -> Uuid,
+        subscriber_name -> Nullable<Text>,
     }
 }
+
+diesel::joinable!(issue_deliveries -> issues (issue_id));
+diesel::joinable!(issue_deliveries -> subscriptions (subscription_id));
+diesel::joinable!(issues -> newsletters (newsletter_id));
+diesel::joinable!(subscription_tokens -> subscriptions (subscription_id));
+diesel::joinable!(subscriptions -> newsletters (newsletter_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    idempotency_keys,
+    issue_deliveries,
+    issues,
+    jobs,
+    newsletters,
+    subscription_tokens,
+    subscriptions,
+);
@@ -0,0 +1,51 @@
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::model::models::ApiKeyRole;
+
+/// marks a string as one of our keys, e.g. for secret scanners
+const PREFIX: &str = "nl_";
+
+/// a fresh key with 244 random bits, shown to whoever mints it and never stored
+pub fn generate() -> String {
+    format!(
+        "{}{}{}",
+        PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// what gets stored and looked up in place of the key, the sha-256 in hex; keys are
+/// random enough that a slow hash buys nothing
+pub fn hash(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeyCommand {
+    /// mints a key and prints it once
+    Create { name: String, role: ApiKeyRole },
+    /// stops a key from authenticating any further requests
+    Revoke(Uuid),
+    /// lists every key, revoked ones included
+    List,
+}
+
+impl ApiKeyCommand {
+    /// parses the arguments following `api-key`
+    pub fn parse(args: &[&str]) -> Result<Self, String> {
+        match args {
+            ["create", name, role] => Ok(ApiKeyCommand::Create {
+                name: name.to_string(),
+                role: role.parse()?,
+            }),
+            ["revoke", id] => Uuid::try_parse(id)
+                .map(ApiKeyCommand::Revoke)
+                .map_err(|_| format!("invalid api key id: {}", id)),
+            ["list"] => Ok(ApiKeyCommand::List),
+            [] => Err("missing api-key command".to_string()),
+            [command, ..] => Err(format!("invalid api-key command: {}", command)),
+        }
+    }
+}
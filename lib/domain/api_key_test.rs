@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod test {
+    use crate::domain::api_key::{generate, hash, ApiKeyCommand};
+    use crate::model::models::ApiKeyRole;
+
+    #[test]
+    fn generate_unique_keys() {
+        // act
+        let first = generate();
+        let second = generate();
+
+        // assert
+        assert!(first.starts_with("nl_"));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        // arrange
+        let key = generate();
+
+        // act
+        let hashed = hash(&key);
+
+        // assert
+        assert_eq!(hashed, hash(&key));
+        assert_ne!(hashed, hash(&generate()));
+        assert!(!hashed.contains(&key));
+    }
+
+    #[test]
+    fn roles_grant_the_roles_below_them() {
+        // assert
+        assert!(ApiKeyRole::Admin.grants(ApiKeyRole::Admin));
+        assert!(ApiKeyRole::Admin.grants(ApiKeyRole::ReadOnly));
+        assert!(ApiKeyRole::Publisher.grants(ApiKeyRole::ReadOnly));
+        assert!(!ApiKeyRole::Publisher.grants(ApiKeyRole::Admin));
+        assert!(!ApiKeyRole::ReadOnly.grants(ApiKeyRole::Publisher));
+    }
+
+    #[test]
+    fn parse_commands() {
+        // act
+        let create = ApiKeyCommand::parse(&["create", "ci", "publisher"]);
+        let unknown_role = ApiKeyCommand::parse(&["create", "ci", "owner"]);
+        let revoke = ApiKeyCommand::parse(&["revoke", "not-a-uuid"]);
+        let missing = ApiKeyCommand::parse(&[]);
+
+        // assert
+        assert_eq!(
+            Ok(ApiKeyCommand::Create {
+                name: "ci".to_string(),
+                role: ApiKeyRole::Publisher
+            }),
+            create
+        );
+        assert!(unknown_role.is_err());
+        assert!(revoke.is_err());
+        assert!(missing.is_err());
+        assert_eq!(Ok(ApiKeyCommand::List), ApiKeyCommand::parse(&["list"]));
+    }
+}
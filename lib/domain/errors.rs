@@ -1,13 +1,32 @@
 use core::fmt;
 use std::error::Error;
+use std::time::Duration;
 
-use axum::{http::StatusCode, response::IntoResponse, Json};
-use serde_json::json;
+use crate::model::models::ErrorResponse;
+use axum::http::header::{RETRY_AFTER, WWW_AUTHENTICATE};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
 
 #[derive(Debug, PartialEq)]
 pub enum DomainError {
     NotFound(String),
-    Validation { field: String, message: String },
+    Validation {
+        field: String,
+        message: String,
+    },
+    /// the request clashes with the current state of a resource
+    Conflict(String),
+    /// the request carries no API key, or one that is unknown or revoked
+    Unauthorized(String),
+    /// the API key's role does not allow the request
+    Forbidden(String),
+    /// the request is well formed but cannot be processed as asked, e.g. it reuses an
+    /// idempotency key for a different request
+    Unprocessable(String),
+    /// the caller used up its quota and may try again after `retry_after`
+    RateLimited {
+        retry_after: Duration,
+    },
     Internal(String),
 }
 
@@ -20,6 +39,25 @@ impl fmt::Display for DomainError {
             DomainError::Validation { field, message } => {
                 write!(f, "validation for field {}, reason = {}", field, message)
             }
+            DomainError::Conflict(msg) => {
+                write!(f, "conflict: {}", msg)
+            }
+            DomainError::Unauthorized(msg) => {
+                write!(f, "unauthorized: {}", msg)
+            }
+            DomainError::Forbidden(msg) => {
+                write!(f, "forbidden: {}", msg)
+            }
+            DomainError::Unprocessable(msg) => {
+                write!(f, "unprocessable: {}", msg)
+            }
+            DomainError::RateLimited { retry_after } => {
+                write!(
+                    f,
+                    "too many requests, retry in {} seconds",
+                    retry_after_secs(retry_after)
+                )
+            }
             DomainError::Internal(msg) => {
                 write!(f, "internal error. reason = {}", msg)
             }
@@ -27,33 +65,78 @@ impl fmt::Display for DomainError {
     }
 }
 
-impl Error for DomainError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
+/// every variant is a leaf, so the default `source` of `None` applies
+impl Error for DomainError {}
+
+/// whole seconds for `Retry-After`, rounded up so a client waiting that long gets through
+fn retry_after_secs(retry_after: &Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
+/// marks a response as carrying a `DomainError` so middleware can tell which kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorVariant(pub &'static str);
+
+impl DomainError {
+    /// the variant name, as used for metric labels
+    pub fn variant(&self) -> &'static str {
         match self {
-            DomainError::NotFound(_) => todo!(),
-            DomainError::Validation {
-                field: _,
-                message: _,
-            } => {
-                todo!()
-            }
-            DomainError::Internal(_) => todo!(),
+            DomainError::NotFound(_) => "not_found",
+            DomainError::Validation { .. } => "validation",
+            DomainError::Conflict(_) => "conflict",
+            DomainError::Unauthorized(_) => "unauthorized",
+            DomainError::Forbidden(_) => "forbidden",
+            DomainError::Unprocessable(_) => "unprocessable",
+            DomainError::RateLimited { .. } => "rate_limited",
+            DomainError::Internal(_) => "internal",
         }
     }
-}
 
-impl DomainError {
-    fn to_response(&self) -> (StatusCode, Json<serde_json::Value>) {
-        let (status, msg) = match self {
-            DomainError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
-            DomainError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            DomainError::Validation { .. } => (StatusCode::BAD_REQUEST, self.to_string()),
+    fn to_response(&self) -> (StatusCode, Json<ErrorResponse>) {
+        let mut body = ErrorResponse {
+            error: self.to_string(),
+            ..Default::default()
         };
-
-        (status, Json(json!({"error": msg})))
+        let status = match self {
+            DomainError::NotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::Conflict(_) => StatusCode::CONFLICT,
+            DomainError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            DomainError::Forbidden(_) => StatusCode::FORBIDDEN,
+            DomainError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            DomainError::RateLimited { retry_after } => {
+                body.retry_after_secs = Some(retry_after_secs(retry_after));
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            DomainError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DomainError::Validation { field, message } => {
+                body.field = Some(field.clone());
+                body.message = Some(message.clone());
+                StatusCode::BAD_REQUEST
+            }
+        };
+        (status, Json(body))
     }
 }
 
 pub fn error_to_response(err: DomainError) -> impl IntoResponse {
-    err.to_response()
+    let (status, body) = err.to_response();
+    let mut headers = HeaderMap::new();
+    match &err {
+        DomainError::Unauthorized(_) => {
+            headers.insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        DomainError::RateLimited { retry_after } => {
+            headers.insert(
+                RETRY_AFTER,
+                HeaderValue::from(retry_after_secs(retry_after)),
+            );
+        }
+        _ => {}
+    }
+    (
+        status,
+        headers,
+        Extension(ErrorVariant(err.variant())),
+        body,
+    )
 }
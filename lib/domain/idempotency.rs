@@ -0,0 +1,119 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::domain::errors::DomainError;
+
+pub const HEADER: &str = "idempotency-key";
+
+/// longer keys are refused rather than stored
+const MAX_KEY_LENGTH: usize = 255;
+
+/// a key a client sends to make retrying a request safe, e.g. a uuid per attempted action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn parse(key: &str) -> Result<Self, DomainError> {
+        let invalid = |message: &str| DomainError::Validation {
+            field: "Idempotency-Key".to_string(),
+            message: message.to_string(),
+        };
+        if key.is_empty() {
+            return Err(invalid("must not be empty"));
+        }
+        if key.len() > MAX_KEY_LENGTH {
+            return Err(invalid("must be at most 255 characters"));
+        }
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(invalid("must be printable ascii without spaces"));
+        }
+        Ok(Self(key.to_string()))
+    }
+}
+
+impl AsRef<str> for IdempotencyKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// who sent a request with an idempotency key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Caller<'a> {
+    /// by its `Authorization` header
+    Authorized(&'a [u8]),
+    /// by its address, for requests without credentials
+    Anonymous(IpAddr),
+}
+
+/// a request made with an idempotency key, as far as telling retries apart goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotentRequest {
+    /// the sha-256 of who made the request, so keys of different callers never meet
+    pub caller: String,
+    pub key: String,
+    /// the sha-256 of what was asked for; a retry must match it
+    pub request_hash: String,
+}
+
+impl IdempotentRequest {
+    pub fn new(
+        key: &IdempotencyKey,
+        caller: Caller,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> Self {
+        let caller = match caller {
+            Caller::Authorized(authorization) => hex::encode(Sha256::digest(authorization)),
+            Caller::Anonymous(ip) => hex::encode(
+                Sha256::new()
+                    .chain_update(b"anonymous\n")
+                    .chain_update(ip.to_string().as_bytes())
+                    .finalize(),
+            ),
+        };
+        let request_hash = hex::encode(
+            Sha256::new()
+                .chain_update(method.as_bytes())
+                .chain_update(b"\n")
+                .chain_update(path_and_query.as_bytes())
+                .chain_update(b"\n")
+                .chain_update(body)
+                .finalize(),
+        );
+        Self {
+            caller,
+            key: key.as_ref().to_string(),
+            request_hash,
+        }
+    }
+}
+
+/// the response a key was first answered with, replayed to every retry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reservation {
+    /// the key is new, or its earlier request was abandoned; the caller serves the request
+    Started,
+    /// an earlier request with the key and the same payload was answered with this
+    Completed(StoredResponse),
+    /// an earlier request with the key and the same payload is still being served
+    InProgress,
+    /// the key was first used for something else
+    PayloadMismatch,
+}
+
+/// whether a response is final for its key; rate limits and server errors are not, so a
+/// retry gets another go
+pub fn is_replayable(status: u16) -> bool {
+    status != 429 && status < 500
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use crate::domain::errors::DomainError;
+    use crate::domain::idempotency::{is_replayable, Caller, IdempotencyKey, IdempotentRequest};
+
+    fn key() -> IdempotencyKey {
+        IdempotencyKey::parse("4f6c1c5e-retry").unwrap()
+    }
+
+    #[test]
+    fn parse_key() {
+        // act
+        let valid = IdempotencyKey::parse("8a1d5a6e-2f2c-4d0c-a3a8-0b3b9f3c2f11");
+        let empty = IdempotencyKey::parse("");
+        let spaces = IdempotencyKey::parse("my key");
+        let too_long = IdempotencyKey::parse(&"k".repeat(256));
+
+        // assert
+        assert!(valid.is_ok());
+        for invalid in [empty, spaces, too_long] {
+            assert!(matches!(
+                invalid,
+                Err(DomainError::Validation { field, .. }) if field == "Idempotency-Key"
+            ));
+        }
+    }
+
+    #[test]
+    fn request_hash_covers_method_path_and_body() {
+        // arrange
+        let request = |method: &str, path: &str, body: &[u8]| {
+            IdempotentRequest::new(&key(), Caller::Authorized(b"Bearer a"), method, path, body)
+                .request_hash
+        };
+        let original = request("POST", "/subscribe", b"{}");
+
+        // act
+        let retry = request("POST", "/subscribe", b"{}");
+        let other_method = request("DELETE", "/subscribe", b"{}");
+        let other_path = request("POST", "/subscribe?x=1", b"{}");
+        let other_body = request("POST", "/subscribe", b"{ }");
+
+        // assert
+        assert_eq!(original, retry);
+        assert_ne!(original, other_method);
+        assert_ne!(original, other_path);
+        assert_ne!(original, other_body);
+    }
+
+    #[test]
+    fn callers_are_told_apart_by_authorization() {
+        // act
+        let alice =
+            IdempotentRequest::new(&key(), Caller::Authorized(b"Bearer a"), "POST", "/", b"");
+        let bob = IdempotentRequest::new(&key(), Caller::Authorized(b"Bearer b"), "POST", "/", b"");
+
+        // assert
+        assert_ne!(alice.caller, bob.caller);
+        assert_eq!(alice.request_hash, bob.request_hash);
+    }
+
+    #[test]
+    fn anonymous_callers_are_told_apart_by_address() {
+        // arrange
+        let address = |last: u8| IpAddr::V4(Ipv4Addr::new(192, 0, 2, last));
+
+        // act
+        let first = IdempotentRequest::new(&key(), Caller::Anonymous(address(1)), "POST", "/", b"");
+        let again = IdempotentRequest::new(&key(), Caller::Anonymous(address(1)), "POST", "/", b"");
+        let other = IdempotentRequest::new(&key(), Caller::Anonymous(address(2)), "POST", "/", b"");
+
+        // assert
+        assert!(!first.caller.is_empty());
+        assert_eq!(first.caller, again.caller);
+        assert_ne!(first.caller, other.caller);
+    }
+
+    #[test]
+    fn only_final_responses_are_replayed() {
+        assert!(is_replayable(201));
+        assert!(is_replayable(404));
+        assert!(!is_replayable(429));
+        assert!(!is_replayable(503));
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// work handed to the background worker through the `jobs` table. Payloads are kept
+/// after the job has run and are shown to operators, so they carry ids only; anything
+/// secret, like a confirmation token, is looked up when the job runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Job {
+    /// emails the pending subscription the link to confirm it with
+    SendConfirmation {
+        subscription_id: Uuid,
+    },
+    PublishIssue {
+        newsletter_id: Uuid,
+        issue_id: Uuid,
+    },
+}
+
+impl Job {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Job::SendConfirmation { .. } => "send_confirmation",
+            Job::PublishIssue { .. } => "publish_issue",
+        }
+    }
+}
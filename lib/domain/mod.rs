@@ -1 +1,12 @@
+pub(crate) mod api_key;
+pub(super) mod api_key_test;
 pub(crate) mod errors;
+pub(crate) mod idempotency;
+pub(super) mod idempotency_test;
+pub(crate) mod jobs;
+pub(crate) mod rate_limit;
+pub(super) mod rate_limit_test;
+pub(crate) mod subscriber;
+pub(super) mod subscriber_test;
+pub(crate) mod unsubscribe_token;
+pub(super) mod unsubscribe_token_test;
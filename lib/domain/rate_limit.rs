@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::adapter::configuration::Quota;
+
+/// past this many tracked keys, buckets that have refilled completely are dropped since
+/// they would behave exactly like a new one
+const PRUNE_ABOVE: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, quota: &Quota, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        let refilled = elapsed.as_secs_f64() / quota.refill.as_secs_f64();
+        self.tokens = (self.tokens + refilled).min(f64::from(quota.capacity));
+        self.updated_at = now;
+    }
+}
+
+/// one token bucket per key, all sharing the same quota
+pub struct TokenBuckets {
+    quota: Quota,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl TokenBuckets {
+    pub fn new(quota: Quota) -> Self {
+        Self {
+            quota,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// takes a token from the bucket of `key`, or tells how long until one is available
+    pub fn acquire(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let quota = &self.quota;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        if buckets.len() > PRUNE_ABOVE {
+            buckets.retain(|_, bucket| {
+                bucket.refill(quota, now);
+                bucket.tokens < f64::from(quota.capacity)
+            });
+        }
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: f64::from(quota.capacity),
+            updated_at: now,
+        });
+        bucket.refill(quota, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(quota.refill.mul_f64(1.0 - bucket.tokens))
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::adapter::configuration::Quota;
+    use crate::domain::rate_limit::TokenBuckets;
+
+    fn buckets() -> TokenBuckets {
+        TokenBuckets::new(Quota {
+            capacity: 2,
+            refill: Duration::from_secs(10),
+        })
+    }
+
+    #[test]
+    fn acquire_until_empty() {
+        // arrange
+        let buckets = buckets();
+        let now = Instant::now();
+
+        // act
+        let first = buckets.acquire("a", now);
+        let second = buckets.acquire("a", now);
+        let third = buckets.acquire("a", now + Duration::from_secs(4));
+
+        // assert
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert_eq!(Err(Duration::from_secs(6)), third);
+    }
+
+    #[test]
+    fn acquire_after_refill() {
+        // arrange
+        let buckets = buckets();
+        let now = Instant::now();
+        buckets.acquire("a", now).unwrap();
+        buckets.acquire("a", now).unwrap();
+
+        // act
+        let refilled = buckets.acquire("a", now + Duration::from_secs(10));
+        let empty_again = buckets.acquire("a", now + Duration::from_secs(10));
+
+        // assert
+        assert!(refilled.is_ok());
+        assert!(empty_again.is_err());
+    }
+
+    #[test]
+    fn keys_have_their_own_bucket() {
+        // arrange
+        let buckets = buckets();
+        let now = Instant::now();
+        buckets.acquire("a", now).unwrap();
+        buckets.acquire("a", now).unwrap();
+
+        // act
+        let other = buckets.acquire("b", now);
+
+        // assert
+        assert!(other.is_ok());
+        assert!(buckets.acquire("a", now).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use lettre::Address;
+
+use super::errors::DomainError;
+
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_LOCAL_PART_LENGTH: usize = 64;
+const MAX_NAME_LENGTH: usize = 256;
+const FORBIDDEN_NAME_CHARACTERS: [char; 9] = ['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
+
+fn invalid(field: &str, message: &str) -> DomainError {
+    DomainError::Validation {
+        field: field.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// email address a subscriber signed up with, checked for syntax and length
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriberEmail(String);
+
+impl SubscriberEmail {
+    pub fn parse(email: &str) -> Result<Self, DomainError> {
+        let email = email.trim();
+        if email.is_empty() {
+            return Err(invalid("email", "email must not be empty"));
+        }
+        if email.len() > MAX_EMAIL_LENGTH {
+            return Err(invalid(
+                "email",
+                &format!("email must be at most {} bytes", MAX_EMAIL_LENGTH),
+            ));
+        }
+        if email.chars().any(char::is_control) {
+            return Err(invalid(
+                "email",
+                "email must not contain control characters",
+            ));
+        }
+        let address: Address = email
+            .parse()
+            .map_err(|_| invalid("email", "email is not a valid address"))?;
+        if address.user().len() > MAX_LOCAL_PART_LENGTH {
+            return Err(invalid(
+                "email",
+                &format!(
+                    "the part before @ must be at most {} bytes",
+                    MAX_LOCAL_PART_LENGTH
+                ),
+            ));
+        }
+        Ok(Self(email.to_string()))
+    }
+}
+
+impl AsRef<str> for SubscriberEmail {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// display name of a subscriber, trimmed and free of characters that break email headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriberName(String);
+
+impl SubscriberName {
+    pub fn parse(name: &str) -> Result<Self, DomainError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(invalid("name", "name must not be empty"));
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(invalid(
+                "name",
+                &format!("name must be at most {} characters", MAX_NAME_LENGTH),
+            ));
+        }
+        if name.chars().any(char::is_control) {
+            return Err(invalid("name", "name must not contain control characters"));
+        }
+        if name.chars().any(|c| FORBIDDEN_NAME_CHARACTERS.contains(&c)) {
+            return Err(invalid(
+                "name",
+                "name must not contain any of / ( ) \" < > \\ { }",
+            ));
+        }
+        Ok(Self(name.to_string()))
+    }
+}
+
+impl AsRef<str> for SubscriberName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
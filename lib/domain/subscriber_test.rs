@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+    use crate::domain::errors::DomainError;
+    use crate::domain::subscriber::{SubscriberEmail, SubscriberName};
+    use fake::{faker::internet::en::SafeEmail, Fake};
+
+    fn assert_invalid(result: Result<impl std::fmt::Debug, DomainError>, expected_field: &str) {
+        match result {
+            Err(DomainError::Validation { field, .. }) => assert_eq!(expected_field, field),
+            other => panic!("expected validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_email() {
+        // arrange
+        let email: String = SafeEmail().fake();
+
+        // act
+        let result = SubscriberEmail::parse(&format!("  {}  ", email));
+
+        // assert
+        assert_eq!(email, result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn invalid_emails() {
+        let too_long = format!("{}@example.com", "a".repeat(250));
+        let long_local_part = format!("{}@example.com", "a".repeat(65));
+        for email in [
+            "",
+            "   ",
+            "ursula.example.com",
+            "@example.com",
+            "ursula@",
+            "ursula@exa mple.com",
+            "ursula\n@example.com",
+            too_long.as_str(),
+            long_local_part.as_str(),
+        ] {
+            assert_invalid(SubscriberEmail::parse(email), "email");
+        }
+    }
+
+    #[test]
+    fn valid_name() {
+        // act
+        let result = SubscriberName::parse("  Ursula Le Guin ");
+
+        // assert
+        assert_eq!("Ursula Le Guin", result.unwrap().as_ref());
+    }
+
+    #[test]
+    fn name_at_length_limit() {
+        // arrange
+        let name = "ё".repeat(256);
+
+        // act
+        let result = SubscriberName::parse(&name);
+
+        // assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn invalid_names() {
+        let too_long = "a".repeat(257);
+        for name in [
+            "",
+            "  ",
+            too_long.as_str(),
+            "Ursula\u{0007}",
+            "Ursula\nLe Guin",
+            "<script>",
+            "Ursula (admin)",
+            "{name}",
+        ] {
+            assert_invalid(SubscriberName::parse(name), "name");
+        }
+    }
+}
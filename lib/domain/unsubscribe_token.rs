@@ -0,0 +1,50 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use super::errors::DomainError;
+use crate::adapter::configuration::SubscriptionConfiguration;
+use crate::api::API_PREFIX;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret: &str, subscription_id: Uuid) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(subscription_id.as_bytes());
+    mac
+}
+
+fn invalid_token() -> DomainError {
+    DomainError::Validation {
+        field: "token".to_string(),
+        message: "invalid unsubscribe token".to_string(),
+    }
+}
+
+/// signs the subscription id so the link works without any other credentials,
+/// formatted as `{subscription_id}.{hmac}` in hex
+pub fn sign(secret: &str, subscription_id: Uuid) -> String {
+    let signature = mac(secret, subscription_id).finalize().into_bytes();
+    format!("{}.{}", subscription_id.simple(), hex::encode(signature))
+}
+
+/// returns the subscription id the token was signed for
+pub fn verify(secret: &str, token: &str) -> Result<Uuid, DomainError> {
+    let (id, signature) = token.split_once('.').ok_or_else(invalid_token)?;
+    let subscription_id = Uuid::try_parse(id).map_err(|_| invalid_token())?;
+    let signature = hex::decode(signature).map_err(|_| invalid_token())?;
+    mac(secret, subscription_id)
+        .verify_slice(&signature)
+        .map_err(|_| invalid_token())?;
+    Ok(subscription_id)
+}
+
+pub fn unsubscribe_url(cfg: &SubscriptionConfiguration, subscription_id: Uuid) -> String {
+    format!(
+        "{}{}/unsubscribe?token={}",
+        cfg.base_url.trim_end_matches('/'),
+        API_PREFIX,
+        sign(&cfg.unsubscribe_secret, subscription_id)
+    )
+}
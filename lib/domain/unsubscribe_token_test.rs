@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+    use crate::domain::errors::DomainError;
+    use crate::domain::unsubscribe_token::{sign, verify};
+    use uuid::Uuid;
+
+    const SECRET: &str = "unsubscribe-secret";
+
+    #[test]
+    fn sign_and_verify() {
+        // arrange
+        let id = Uuid::new_v4();
+
+        // act
+        let token = sign(SECRET, id);
+
+        // assert
+        assert_eq!(id, verify(SECRET, &token).unwrap());
+    }
+
+    #[test]
+    fn verify_wrong_secret() {
+        // arrange
+        let token = sign(SECRET, Uuid::new_v4());
+
+        // act
+        let result = verify("another-secret", &token);
+
+        // assert
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::Validation { .. }
+        ));
+    }
+
+    #[test]
+    fn verify_tampered_subscription_id() {
+        // arrange
+        let token = sign(SECRET, Uuid::new_v4());
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{}", Uuid::new_v4().simple(), signature);
+
+        // act
+        let result = verify(SECRET, &forged);
+
+        // assert
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::Validation { .. }
+        ));
+    }
+
+    #[test]
+    fn verify_malformed_token() {
+        // act & assert
+        for token in [
+            "",
+            "no-separator",
+            "not-a-uuid.abcd",
+            &format!("{}.zz", Uuid::new_v4()),
+        ] {
+            assert!(matches!(
+                verify(SECRET, token).unwrap_err(),
+                DomainError::Validation { .. }
+            ));
+        }
+    }
+}
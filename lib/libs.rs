@@ -1,59 +1,436 @@
 mod adapter;
 mod domain;
+mod metrics;
 pub mod model;
 mod routes;
+mod shutdown;
+mod telemetry;
+mod worker;
 
 pub mod api {
-    use crate::adapter::{configuration::DatabaseConfiguration, repository::Repository};
-    use crate::{adapter, routes};
+    use crate::adapter::configuration::{LogFormat, ServerConfiguration};
+    use crate::adapter::email_client;
+    use crate::adapter::migrations;
+    use crate::adapter::repository::ApiKeyRepository;
+    use crate::domain::api_key;
+    use crate::model::models::{self, ApiKeyRole};
+    use crate::routes::route_table::RouteTable;
+    use crate::{adapter, routes, telemetry, worker};
     use axum::extract::{MatchedPath, Request};
     use axum::response::Response;
-    use axum::{
-        routing::{get, post},
-        Router,
-    };
-    use std::sync::{Arc, Mutex};
-    use std::time::Duration;
+    use axum::Router;
+    use std::future::IntoFuture;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+    use tokio::task::JoinHandle;
+    use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
     use tower_http::trace::TraceLayer;
     use tracing::{error, info, info_span, warn, Span};
     use tracing_subscriber::layer::SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::Layer;
 
     use std::sync::Once;
     static TRACING: Once = Once::new();
 
-    fn setup() {
+    fn setup(settings: &Settings) {
+        let logging = &settings.logging;
         TRACING.call_once(|| {
+            let fmt = match logging.format {
+                LogFormat::Pretty => tracing_subscriber::fmt::layer().boxed(),
+                LogFormat::Json => tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(false)
+                    .boxed(),
+            };
             tracing_subscriber::registry()
-                .with(tracing_subscriber::fmt::layer())
+                .with(fmt.with_filter(logging.env_filter()))
+                .with(telemetry::layer(&settings.telemetry))
                 .init();
         });
     }
 
+    pub use crate::adapter::configuration::{Quota, Settings};
+
+    /// header carrying the id that ties a request to its log lines and error body
+    pub const REQUEST_ID_HEADER: &str = "x-request-id";
+    /// every api route is served under this prefix, so a breaking change can move to `/v2`
+    pub const API_PREFIX: &str = "/v1";
+    pub use crate::adapter::memory_repository::InMemoryRepository;
+    pub use crate::adapter::migrations::MigrationCommand;
+    pub use crate::adapter::repository::Repository;
+    pub use crate::domain::api_key::ApiKeyCommand;
+    pub use crate::routes::app::Application;
+    pub use crate::shutdown::{signal as shutdown_signal, Shutdown};
+    pub use crate::telemetry::shutdown as shutdown_telemetry;
+
+    /// starts the background job worker on the current tokio runtime, on the pool of `repo`;
+    /// it stops taking jobs once `shutdown` is triggered
+    pub fn spawn_worker(
+        settings: &Settings,
+        repo: Repository,
+        shutdown: Shutdown,
+    ) -> JoinHandle<()> {
+        setup(settings);
+        let email_client = email_client::new_email_client(&settings.email)
+            .unwrap_or_else(|err| panic!("failed to instantiate email client: {}", err));
+        let job_worker = worker::job_worker::JobWorker::new(
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo),
+            email_client,
+            settings.subscription.clone(),
+            settings.worker.clone(),
+        );
+        tokio::spawn(worker::job_worker::run(job_worker, shutdown))
+    }
+
+    /// serves `app` until `shutdown` is triggered. Readiness then reports unhealthy while
+    /// the listener keeps accepting for the shutdown delay, after which in-flight requests
+    /// and `workers` get the drain timeout to finish before they are abandoned
+    pub async fn serve(
+        listener: tokio::net::TcpListener,
+        app: Router,
+        cfg: &ServerConfiguration,
+        shutdown: Shutdown,
+        workers: Vec<JoinHandle<()>>,
+    ) -> std::io::Result<()> {
+        let delay = cfg.shutdown_delay;
+        let stop_accepting = {
+            let shutdown = shutdown.clone();
+            async move {
+                shutdown.triggered().await;
+                info!(
+                    "shutting down, no longer ready; accepting for another {:?}",
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                info!("stopped accepting connections, draining in-flight requests");
+            }
+        };
+        let mut server = tokio::spawn(
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .with_graceful_shutdown(stop_accepting)
+            .into_future(),
+        );
+
+        tokio::select! {
+            res = &mut server => return res.map_err(std::io::Error::other)?,
+            _ = shutdown.triggered() => {},
+        }
+
+        let deadline = tokio::time::Instant::now() + delay + cfg.drain_timeout;
+        let res = match tokio::time::timeout_at(deadline, &mut server).await {
+            Ok(res) => res.map_err(std::io::Error::other)?,
+            Err(_) => {
+                warn!("drain timeout elapsed, dropping in-flight requests");
+                server.abort();
+                Ok(())
+            }
+        };
+        for mut handle in workers {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                warn!("drain timeout elapsed, abandoning a background worker");
+                handle.abort();
+            }
+        }
+        // the pool closes once the router and the workers drop the last repository clones;
+        // tasks aborted above release theirs when the runtime tears them down
+        let _ = tokio::task::spawn_blocking(telemetry::shutdown).await;
+        info!("shutdown complete");
+        res
+    }
+
+    /// runs `newsletter_service migrate <command>`, returning the lines to report
+    pub fn migrate(settings: &Settings, command: MigrationCommand) -> Result<Vec<String>, String> {
+        let cfg = &settings.database;
+        let res = match command {
+            MigrationCommand::Up => migrations::run_pending(cfg).map(|applied| {
+                if applied.is_empty() {
+                    vec!["no pending migrations".to_string()]
+                } else {
+                    applied
+                        .into_iter()
+                        .map(|v| format!("applied {}", v))
+                        .collect()
+                }
+            }),
+            MigrationCommand::Down => {
+                migrations::revert_last(cfg).map(|v| vec![format!("reverted {}", v)])
+            }
+            MigrationCommand::Status => migrations::status(cfg).map(|all| {
+                all.into_iter()
+                    .map(|(name, applied)| {
+                        format!("[{}] {}", if applied { "X" } else { " " }, name)
+                    })
+                    .collect()
+            }),
+        };
+        res.map_err(|err| err.to_string())
+    }
+
+    /// mints a key with `role`, returning it alongside the stored record; only its hash
+    /// is kept, so the key cannot be shown again
+    pub async fn create_api_key(
+        settings: &Settings,
+        name: &str,
+        role: ApiKeyRole,
+    ) -> Result<(models::ApiKey, String), String> {
+        let repo = Repository::new(&settings.database).map_err(|err| err.to_string())?;
+        let key = api_key::generate();
+        let record = repo
+            .add_api_key(
+                name.to_string(),
+                role,
+                api_key::hash(&key),
+                SystemTime::now(),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok((record, key))
+    }
+
+    /// runs `newsletter_service api-key <command>`, returning the lines to report
+    pub async fn api_key(
+        settings: &Settings,
+        command: ApiKeyCommand,
+    ) -> Result<Vec<String>, String> {
+        let describe = |key: &models::ApiKey| {
+            format!(
+                "{} {} {}{}",
+                key.api_key_id,
+                key.role.as_str(),
+                key.name,
+                if key.revoked_at.is_some() {
+                    " (revoked)"
+                } else {
+                    ""
+                }
+            )
+        };
+        match command {
+            ApiKeyCommand::Create { name, role } => {
+                let (record, key) = create_api_key(settings, &name, role).await?;
+                Ok(vec![format!("created {}", describe(&record)), key])
+            }
+            ApiKeyCommand::Revoke(id) => {
+                let repo = Repository::new(&settings.database).map_err(|err| err.to_string())?;
+                let record = repo
+                    .revoke_api_key(id, SystemTime::now())
+                    .await
+                    .map_err(|err| err.to_string())?;
+                Ok(vec![format!("revoked {}", describe(&record))])
+            }
+            ApiKeyCommand::List => {
+                let repo = Repository::new(&settings.database).map_err(|err| err.to_string())?;
+                let keys = repo.get_api_keys().await.map_err(|err| err.to_string())?;
+                Ok(keys.iter().map(describe).collect())
+            }
+        }
+    }
+
+    /// builds the application from `Settings::load`, panicking when the settings are invalid
     pub fn app() -> Router {
-        setup();
-        let cfg = DatabaseConfiguration::new();
-        let repo = Repository::new(&cfg);
-        if repo.is_err() {
-            panic!("failed to instantiate repo")
+        let settings = Settings::load().unwrap_or_else(|err| panic!("invalid settings: {}", err));
+        app_with_settings(&settings, Shutdown::new())
+    }
+
+    /// builds the application from `settings`; readiness reports unhealthy once `shutdown`
+    /// is triggered
+    pub fn app_with_settings(settings: &Settings, shutdown: Shutdown) -> Router {
+        app_with_repository(settings, repository(settings), shutdown)
+    }
+
+    /// opens the database pool, first running pending migrations when `settings` ask for it;
+    /// clones share the pool, so the server and the worker can use one between them
+    pub fn repository(settings: &Settings) -> Repository {
+        setup(settings);
+        let cfg = &settings.database;
+        if cfg.run_migrations {
+            match migrations::run_pending(cfg) {
+                Ok(applied) => info!("applied {} pending migrations", applied.len()),
+                Err(err) => panic!("failed to run migrations: {}", err),
+            }
         }
-        let repo = repo.unwrap();
-        let repo: Arc<Mutex<dyn adapter::repository::SubscriptionRepository + Send + Sync>> =
-            Arc::new(Mutex::new(repo));
-        let application = routes::app::Application::new(repo);
-        let application = Arc::new(application);
-        Router::new()
-            .route("/echo", get(routes::echo::handler))
-            .route("/health_check", get(routes::health_check::handler))
+        Repository::new(cfg).unwrap_or_else(|err| panic!("failed to instantiate repo: {}", err))
+    }
+
+    /// builds the application from `settings` on the pool of `repo`; readiness reports
+    /// unhealthy once `shutdown` is triggered
+    pub fn app_with_repository(
+        settings: &Settings,
+        repo: Repository,
+        shutdown: Shutdown,
+    ) -> Router {
+        setup(settings);
+        let newsletter_repo: Arc<dyn adapter::repository::NewsletterRepository> =
+            Arc::new(repo.clone());
+        let issue_repo: Arc<dyn adapter::repository::IssueRepository> = Arc::new(repo.clone());
+        let job_repo: Arc<dyn adapter::repository::JobRepository> = Arc::new(repo.clone());
+        let health_repo: Arc<dyn adapter::repository::HealthRepository> = Arc::new(repo.clone());
+        let api_key_repo: Arc<dyn adapter::repository::ApiKeyRepository> = Arc::new(repo.clone());
+        let idempotency_repo: Arc<dyn adapter::repository::IdempotencyRepository> =
+            Arc::new(repo.clone());
+        let repo: Arc<dyn adapter::repository::SubscriptionRepository> = Arc::new(repo);
+        let application = routes::app::Application::new(
+            repo,
+            newsletter_repo,
+            issue_repo,
+            job_repo,
+            health_repo,
+            api_key_repo,
+            settings.subscription.clone(),
+            settings.worker.clone(),
+        )
+        .with_shutdown(shutdown)
+        .with_rate_limits(&settings.rate_limit)
+        .with_idempotency(idempotency_repo, settings.idempotency.clone());
+        app_with(application)
+    }
+
+    /// the health and metrics routes probes and scrapers poll; they are not part of the
+    /// versioned api and stay at the root
+    pub(crate) fn ops_route_table() -> RouteTable {
+        use axum::http::Method;
+        use routes::{health_check, metrics};
+
+        RouteTable::default()
+            .route(
+                Method::GET,
+                "/health_check",
+                health_check::health_check_handler,
+            )
+            .route(Method::GET, "/health/live", health_check::live_handler)
+            .route(Method::GET, "/health/ready", health_check::ready_handler)
+            .route(Method::GET, "/metrics", metrics::handler)
+    }
+
+    /// every api route, relative to `API_PREFIX`
+    pub(crate) fn route_table() -> RouteTable {
+        use axum::http::Method;
+        use routes::subscriptions as subs;
+        use routes::{echo, issues, jobs, newsletters, openapi};
+
+        RouteTable::default()
+            .route(Method::GET, "/echo", echo::handler)
+            .route(Method::GET, "/openapi.json", openapi::handler)
             .route(
+                Method::POST,
                 "/subscribe",
-                post(routes::subscriptions::create_subscription_handler)
-                    .delete(routes::subscriptions::remove_subscription_handler),
+                subs::create_subscription_handler,
             )
             .route(
+                Method::DELETE,
+                "/subscribe",
+                subs::remove_subscription_handler,
+            )
+            .route(
+                Method::GET,
                 "/subscriptions",
-                get(routes::subscriptions::get_subscription_handler),
+                subs::get_subscription_handler,
+            )
+            .route(
+                Method::GET,
+                "/subscriptions/:subscription_id",
+                subs::get_subscription_by_id_handler,
+            )
+            .route(
+                Method::DELETE,
+                "/subscriptions/:subscription_id",
+                subs::delete_subscription_handler,
+            )
+            .route(
+                Method::GET,
+                "/subscriptions/confirm",
+                subs::confirm_subscription_handler,
             )
+            .route(
+                Method::GET,
+                "/unsubscribe",
+                subs::unsubscribe_preview_handler,
+            )
+            .route(Method::POST, "/unsubscribe", subs::unsubscribe_handler)
+            .route(
+                Method::POST,
+                "/newsletters",
+                newsletters::create_newsletter_handler,
+            )
+            .route(
+                Method::GET,
+                "/newsletters",
+                newsletters::get_newsletters_handler,
+            )
+            .route(
+                Method::GET,
+                "/newsletters/:newsletter_id",
+                newsletters::get_newsletter_handler,
+            )
+            .route(
+                Method::PUT,
+                "/newsletters/:newsletter_id",
+                newsletters::update_newsletter_handler,
+            )
+            .route(
+                Method::DELETE,
+                "/newsletters/:newsletter_id",
+                newsletters::remove_newsletter_handler,
+            )
+            .route(
+                Method::POST,
+                "/newsletters/:newsletter_id/issues",
+                issues::create_issue_handler,
+            )
+            .route(
+                Method::GET,
+                "/newsletters/:newsletter_id/issues/:issue_id",
+                issues::get_issue_handler,
+            )
+            .route(
+                Method::POST,
+                "/newsletters/:newsletter_id/issues/:issue_id/publish",
+                issues::publish_issue_handler,
+            )
+            .route(
+                Method::GET,
+                "/admin/subscriptions",
+                subs::list_subscriptions_handler,
+            )
+            .route(Method::GET, "/jobs/dead", jobs::get_dead_jobs_handler)
+            .route(
+                Method::POST,
+                "/jobs/:job_id/requeue",
+                jobs::requeue_job_handler,
+            )
+    }
+
+    /// builds the router around an already assembled application, e.g. one backed by
+    /// in-memory repositories
+    pub fn app_with(application: Application) -> Router {
+        let application = Arc::new(application);
+        // the api routes are also served from the root, where they were before `API_PREFIX`,
+        // for callers that have not moved yet
+        let aliases = route_table().into_router().layer(axum::middleware::from_fn(
+            routes::response::unversioned_alias,
+        ));
+        let router = Router::new()
+            .nest(API_PREFIX, route_table().into_router())
+            .merge(aliases)
+            .merge(ops_route_table().into_router());
+        #[cfg(feature = "swagger-ui")]
+        let router = router.merge(routes::openapi::swagger_ui());
+        router
+            .layer(axum::middleware::from_fn(routes::rate_limit::limit_by_ip))
+            .layer(axum::middleware::from_fn(routes::idempotency::replay))
+            .layer(axum::middleware::from_fn(routes::request_id::scope))
+            .layer(axum::middleware::from_fn(telemetry::propagate))
             .layer(
                 TraceLayer::new_for_http()
                     .make_span_with(|request: &Request<_>| {
@@ -61,21 +438,29 @@ pub mod api {
                             .extensions()
                             .get::<MatchedPath>()
                             .map(MatchedPath::as_str);
+                        let request_id = request
+                            .headers()
+                            .get(REQUEST_ID_HEADER)
+                            .and_then(|id| id.to_str().ok());
 
-                        info_span!(
+                        // the query is left out: confirm and unsubscribe links carry their
+                        // tokens there
+                        let span = info_span!(
                             "http_request",
                             method = ?request.method(),
                             matched_path,
-                            uri = ?request.uri(),
-                            some_other_field = tracing::field::Empty,
-                        )
+                            path = request.uri().path(),
+                            request_id,
+                        );
+                        telemetry::set_remote_parent(&span, request.headers());
+                        span
                     })
                     .on_request(|request: &Request<_>, span: &Span| {
                         info!(
                             parent: span,
                                 "Started {} request to {}",
                                 request.method(),
-                                request.uri(),
+                                request.uri().path(),
                         )
                     })
                     .on_response(|response: &Response, latency: Duration, span: &Span| {
@@ -114,6 +499,11 @@ pub mod api {
                         }
                     }),
             )
+            .layer(axum::middleware::from_fn(routes::metrics::track_requests))
             .layer(axum::Extension(application))
+            // an id the caller sent is kept, otherwise one is generated; either way it is
+            // echoed back in the response
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
     }
 }
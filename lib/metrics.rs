@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use crate::adapter::repository::PoolStatistics;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+/// the collectors behind `/metrics`, each registered with a registry of its own so that
+/// applications built side by side, as the tests do, never share counts
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    pool_connections: IntGauge,
+    pool_idle_connections: IntGauge,
+    pool_max_size: IntGauge,
+    subscriptions_created: IntCounter,
+    subscriptions_removed: IntCounter,
+    domain_errors: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests served"),
+            &["method", "path", "status"],
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "time taken to serve HTTP requests",
+            ),
+            &["method", "path", "status"],
+        )
+        .unwrap();
+        let pool_connections = IntGauge::new(
+            "db_pool_connections",
+            "connections currently held by the database pool",
+        )
+        .unwrap();
+        let pool_idle_connections = IntGauge::new(
+            "db_pool_idle_connections",
+            "idle connections in the database pool",
+        )
+        .unwrap();
+        let pool_max_size =
+            IntGauge::new("db_pool_max_size", "connections the database pool may open").unwrap();
+        let subscriptions_created =
+            IntCounter::new("subscriptions_created_total", "subscriptions created").unwrap();
+        let subscriptions_removed = IntCounter::new(
+            "subscriptions_removed_total",
+            "subscriptions removed or unsubscribed",
+        )
+        .unwrap();
+        let domain_errors = IntCounterVec::new(
+            Opts::new("domain_errors_total", "errors returned by the API, by kind"),
+            &["variant"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry
+            .register(Box::new(http_request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pool_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pool_idle_connections.clone()))
+            .unwrap();
+        registry.register(Box::new(pool_max_size.clone())).unwrap();
+        registry
+            .register(Box::new(subscriptions_created.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(subscriptions_removed.clone()))
+            .unwrap();
+        registry.register(Box::new(domain_errors.clone())).unwrap();
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            pool_connections,
+            pool_idle_connections,
+            pool_max_size,
+            subscriptions_created,
+            subscriptions_removed,
+            domain_errors,
+        }
+    }
+
+    pub fn observe_request(&self, method: &str, path: &str, status: u16, latency: Duration) {
+        let status = status.to_string();
+        let labels = [method, path, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(latency.as_secs_f64());
+    }
+
+    pub fn record_pool(&self, pool: &PoolStatistics) {
+        self.pool_connections.set(pool.connections.into());
+        self.pool_idle_connections.set(pool.idle_connections.into());
+        self.pool_max_size.set(pool.max_size.into());
+    }
+
+    pub fn subscription_created(&self) {
+        self.subscriptions_created.inc();
+    }
+
+    pub fn subscription_removed(&self) {
+        self.subscriptions_removed.inc();
+    }
+
+    /// counts an error response by `DomainError::variant`
+    pub fn domain_error(&self, variant: &str) {
+        self.domain_errors.with_label_values(&[variant]).inc();
+    }
+
+    /// every collector in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,43 +2,364 @@ use chrono::DateTime;
 use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionStatus {
+    #[default]
+    PendingConfirmation,
+    Confirmed,
+}
+
+impl SubscriptionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionStatus::PendingConfirmation => "pending_confirmation",
+            SubscriptionStatus::Confirmed => "confirmed",
+        }
+    }
+}
+
+impl FromStr for SubscriptionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending_confirmation" => Ok(SubscriptionStatus::PendingConfirmation),
+            "confirmed" => Ok(SubscriptionStatus::Confirmed),
+            other => Err(format!("unknown subscription status: {}", other)),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Subscription {
     pub email: Option<String>,
     pub subscription_id: String,
-    pub subscription_name: String,
+    /// the name the subscriber gave; null for subscriptions made before newsletters had
+    /// names of their own, which only recorded the newsletter's
+    pub subscriber_name: Option<String>,
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
     pub subscribe_since: DateTime<Utc>,
+    #[serde(default)]
+    pub status: SubscriptionStatus,
+    pub newsletter_id: String,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct CreateSubscriptionRequest {
     pub email: String,
+    /// the subscriber's own name
     pub name: String,
+    pub newsletter_id: String,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetSubscriptionRequest {
     pub email: String,
+    /// also return subscriptions that have not been confirmed yet
+    #[serde(default)]
+    pub include_pending: bool,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct GetSubscriptionsResponse {
     pub resp: Vec<Subscription>,
 }
 
-#[derive(Serialize)]
+/// query string of the admin subscription listing; every filter that is set must match
+#[derive(Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListSubscriptionsRequest {
+    pub email: Option<String>,
+    pub newsletter_id: Option<String>,
+    pub status: Option<SubscriptionStatus>,
+    /// inclusive, RFC 3339
+    pub subscribed_from: Option<DateTime<Utc>>,
+    /// exclusive, RFC 3339
+    pub subscribed_until: Option<DateTime<Utc>>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ListSubscriptionsResponse {
+    pub subscriptions: Vec<Subscription>,
+    /// matching subscriptions across every page
+    pub total: u64,
+    /// absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct SubscriptionResponse {
     pub message: String,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct RemoveSubscriptionRequest {
     pub subscription_id: String,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct RemoveSubscriptionResponse {
     pub subscription: Subscription,
 }
+
+#[derive(Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConfirmSubscriptionRequest {
+    pub token: String,
+}
+
+#[derive(Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UnsubscribeRequest {
+    pub token: String,
+}
+
+/// the subscription an unsubscribe link points at, before anything is removed
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct UnsubscribePreviewResponse {
+    pub subscription: Subscription,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ConfirmSubscriptionResponse {
+    pub subscription: Subscription,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Newsletter {
+    pub newsletter_id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct CreateNewsletterRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct UpdateNewsletterRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct GetNewslettersResponse {
+    pub resp: Vec<Newsletter>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct RemoveNewsletterResponse {
+    pub newsletter: Newsletter,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Issue {
+    pub issue_id: String,
+    pub newsletter_id: String,
+    pub title: String,
+    pub text_body: String,
+    pub html_body: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[schema(value_type = Option<i64>)]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct CreateIssueRequest {
+    pub title: String,
+    pub text_body: String,
+    pub html_body: String,
+}
+
+/// per-recipient delivery counts of an issue
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DeliverySummary {
+    pub pending: u64,
+    pub sent: u64,
+    pub failed: u64,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct IssueResponse {
+    pub issue: Issue,
+    pub deliveries: DeliverySummary,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    #[default]
+    Queued,
+    Running,
+    Succeeded,
+    /// failed on every attempt and waits to be inspected and requeued
+    Dead,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Dead => "dead",
+        }
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(JobStatus::Queued),
+            "running" => Ok(JobStatus::Running),
+            "succeeded" => Ok(JobStatus::Succeeded),
+            "dead" => Ok(JobStatus::Dead),
+            other => Err(format!("unknown job status: {}", other)),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Job {
+    pub job_id: String,
+    pub kind: String,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
+    pub run_at: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct GetJobsResponse {
+    pub resp: Vec<Job>,
+}
+
+/// what an API key may do; each role may also do everything the roles below it may
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyRole {
+    /// manages subscriptions and jobs
+    Admin,
+    /// manages newsletters and publishes issues
+    Publisher,
+    /// reads subscriptions, issues and jobs
+    #[default]
+    ReadOnly,
+}
+
+impl ApiKeyRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyRole::Admin => "admin",
+            ApiKeyRole::Publisher => "publisher",
+            ApiKeyRole::ReadOnly => "read_only",
+        }
+    }
+
+    /// whether a key with this role may act where `required` is needed
+    pub fn grants(&self, required: ApiKeyRole) -> bool {
+        match self {
+            ApiKeyRole::Admin => true,
+            ApiKeyRole::Publisher => required != ApiKeyRole::Admin,
+            ApiKeyRole::ReadOnly => required == ApiKeyRole::ReadOnly,
+        }
+    }
+}
+
+impl FromStr for ApiKeyRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(ApiKeyRole::Admin),
+            "publisher" => Ok(ApiKeyRole::Publisher),
+            "read_only" => Ok(ApiKeyRole::ReadOnly),
+            other => Err(format!("unknown api key role: {}", other)),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKey {
+    pub api_key_id: String,
+    pub name: String,
+    pub role: ApiKeyRole,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[schema(value_type = i64)]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[schema(value_type = Option<i64>)]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Unhealthy,
+}
+
+/// state of one dependency checked by the readiness probe
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DependencyCheck {
+    pub name: String,
+    pub status: HealthStatus,
+    /// whether a failure of this check makes the service unready
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    pub status: HealthStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<DependencyCheck>,
+}
+
+/// body of every error response
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// the invalid field of a validation error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// how long a rate limited caller should wait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+    /// the id of the request in the service's logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
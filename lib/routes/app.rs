@@ -1,15 +1,98 @@
 use std::sync::Arc;
-use std::sync::Mutex;
 
+use crate::adapter::configuration::{
+    IdempotencyConfiguration, RateLimitConfiguration, SubscriptionConfiguration,
+    WorkerConfiguration,
+};
+use crate::adapter::memory_repository::InMemoryRepository;
 use crate::adapter::repository;
+use crate::metrics::Metrics;
+use crate::routes::idempotency::Idempotency;
+use crate::routes::rate_limit::RateLimiter;
+use crate::shutdown::Shutdown;
 
 #[derive(Clone)]
 pub struct Application {
-    pub repo: Arc<Mutex<dyn repository::SubscriptionRepository + Send + Sync>>,
+    pub repo: Arc<dyn repository::SubscriptionRepository>,
+    pub newsletter_repo: Arc<dyn repository::NewsletterRepository>,
+    pub issue_repo: Arc<dyn repository::IssueRepository>,
+    pub job_repo: Arc<dyn repository::JobRepository>,
+    pub health_repo: Arc<dyn repository::HealthRepository>,
+    pub api_key_repo: Arc<dyn repository::ApiKeyRepository>,
+    pub subscription_cfg: Arc<SubscriptionConfiguration>,
+    pub worker_cfg: Arc<WorkerConfiguration>,
+    pub shutdown: Shutdown,
+    pub metrics: Arc<Metrics>,
+    pub rate_limiter: Arc<RateLimiter>,
+    /// without it the `Idempotency-Key` header is ignored
+    pub idempotency: Option<Idempotency>,
 }
 
 impl Application {
-    pub fn new(repo: Arc<Mutex<dyn repository::SubscriptionRepository + Send + Sync>>) -> Self {
-        Self { repo }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repo: Arc<dyn repository::SubscriptionRepository>,
+        newsletter_repo: Arc<dyn repository::NewsletterRepository>,
+        issue_repo: Arc<dyn repository::IssueRepository>,
+        job_repo: Arc<dyn repository::JobRepository>,
+        health_repo: Arc<dyn repository::HealthRepository>,
+        api_key_repo: Arc<dyn repository::ApiKeyRepository>,
+        subscription_cfg: SubscriptionConfiguration,
+        worker_cfg: WorkerConfiguration,
+    ) -> Self {
+        Self {
+            repo,
+            newsletter_repo,
+            issue_repo,
+            job_repo,
+            health_repo,
+            api_key_repo,
+            subscription_cfg: Arc::new(subscription_cfg),
+            worker_cfg: Arc::new(worker_cfg),
+            shutdown: Shutdown::new(),
+            metrics: Arc::new(Metrics::new()),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            idempotency: None,
+        }
+    }
+
+    /// every repository backed by `repo`, so the routes run without a database
+    pub fn in_memory(
+        repo: &InMemoryRepository,
+        subscription_cfg: SubscriptionConfiguration,
+        worker_cfg: WorkerConfiguration,
+    ) -> Self {
+        Self::new(
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            subscription_cfg,
+            worker_cfg,
+        )
+    }
+
+    /// shares `shutdown` so readiness reports unhealthy once it is triggered
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// enforces `cfg`; without it nothing is rate limited
+    pub fn with_rate_limits(mut self, cfg: &RateLimitConfiguration) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(cfg));
+        self
+    }
+
+    /// replays the stored response to retries of requests with an `Idempotency-Key`
+    pub fn with_idempotency(
+        mut self,
+        repo: Arc<dyn repository::IdempotencyRepository>,
+        cfg: IdempotencyConfiguration,
+    ) -> Self {
+        self.idempotency = Some(Idempotency { repo, cfg });
+        self
     }
 }
@@ -0,0 +1,129 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::adapter::repository::ApiKeyRepository;
+use crate::domain::api_key;
+use crate::domain::errors::{self as domain_errors, DomainError};
+use crate::model::models::{self as api_models, ApiKeyRole};
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+
+/// the role a route requires, named by one of the marker types below
+pub trait RequiredRole: Send + Sync {
+    const ROLE: ApiKeyRole;
+}
+
+pub struct Admin;
+pub struct Publisher;
+pub struct ReadOnly;
+
+impl RequiredRole for Admin {
+    const ROLE: ApiKeyRole = ApiKeyRole::Admin;
+}
+
+impl RequiredRole for Publisher {
+    const ROLE: ApiKeyRole = ApiKeyRole::Publisher;
+}
+
+impl RequiredRole for ReadOnly {
+    const ROLE: ApiKeyRole = ApiKeyRole::ReadOnly;
+}
+
+/// rejects the request unless its `Authorization: Bearer` key has a role granting `R`;
+/// handlers take it ahead of any extractor reading the body
+pub struct Authorized<R: RequiredRole> {
+    pub api_key: api_models::ApiKey,
+    role: PhantomData<R>,
+}
+
+/// like `Authorized`, but lets a request without an `Authorization` header through as
+/// `None`; for routes that were anonymous before keys, until their callers send one
+pub struct MaybeAuthorized<R: RequiredRole>(pub Option<Authorized<R>>);
+
+fn bearer(headers: &HeaderMap) -> Result<&str, DomainError> {
+    let value = headers
+        .get(AUTHORIZATION)
+        .ok_or_else(|| DomainError::Unauthorized("missing API key".to_string()))?;
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| {
+            DomainError::Unauthorized("expected an Authorization: Bearer header".to_string())
+        })
+}
+
+pub(crate) async fn authorize(
+    headers: &HeaderMap,
+    required: ApiKeyRole,
+    repo: &dyn ApiKeyRepository,
+) -> Result<api_models::ApiKey, DomainError> {
+    let key = bearer(headers)?;
+    let api_key = repo
+        .find_api_key(api_key::hash(key))
+        .await?
+        .ok_or_else(|| DomainError::Unauthorized("unknown or revoked API key".to_string()))?;
+    if !api_key.role.grants(required) {
+        return Err(DomainError::Forbidden(format!(
+            "the {} role is required",
+            required.as_str()
+        )));
+    }
+    Ok(api_key)
+}
+
+#[async_trait]
+impl<S, R> FromRequestParts<S> for Authorized<R>
+where
+    S: Send + Sync,
+    R: RequiredRole,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let app = parts
+            .extensions
+            .get::<Arc<super::app::Application>>()
+            .cloned()
+            .ok_or_else(|| {
+                domain_errors::error_to_response(DomainError::Internal(
+                    "application is not installed".to_string(),
+                ))
+                .into_response()
+            })?;
+        match authorize(&parts.headers, R::ROLE, app.api_key_repo.as_ref()).await {
+            Ok(api_key) => {
+                tracing::debug!("authorized as {} ({})", api_key.name, api_key.api_key_id);
+                Ok(Self {
+                    api_key,
+                    role: PhantomData,
+                })
+            }
+            Err(err) => Err(domain_errors::error_to_response(err).into_response()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S, R> FromRequestParts<S> for MaybeAuthorized<R>
+where
+    S: Send + Sync,
+    R: RequiredRole,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(Self(None));
+        }
+        Authorized::from_request_parts(parts, state)
+            .await
+            .map(|authorized| Self(Some(authorized)))
+    }
+}
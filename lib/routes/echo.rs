@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use axum::{extract::Query, Extension};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[allow(dead_code)]
 pub struct Params {
     name: Option<String>,
@@ -14,6 +16,13 @@ fn echo(Query(params): Query<Params>) -> String {
     format!("Hello, {}!", name)
 }
 
+#[utoipa::path(
+    get,
+    path = "/echo",
+    tag = "operations",
+    params(Params),
+    responses((status = 200, description = "a greeting", body = String, content_type = "text/plain"))
+)]
 pub async fn handler(
     Extension(_app): axum::Extension<Arc<super::app::Application>>,
     query: axum::extract::Query<super::echo::Params>,
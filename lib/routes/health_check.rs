@@ -1,15 +1,141 @@
 use std::sync::Arc;
 
+use axum::http::{header::CONTENT_TYPE, StatusCode};
 use axum::response::Response;
-use axum::{body, http, Extension};
+use axum::{body, Extension};
+use serde_json::json;
 
-fn health_check() -> Response {
+use crate::adapter::repository::DatabaseHealth;
+use crate::model::models as api_models;
+use api_models::HealthStatus;
+
+fn json_response(status: StatusCode, health: &api_models::HealthResponse) -> Response {
+    let json_body = serde_json::to_string(health).unwrap_or_else(|_| "{}".to_string());
     Response::builder()
-        .status(http::StatusCode::OK)
-        .body(body::Body::from("healthy"))
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body::Body::from(json_body))
         .unwrap()
 }
 
-pub async fn handler(Extension(_app): axum::Extension<Arc<super::app::Application>>) -> Response {
-    health_check()
+fn status_of(healthy: bool) -> HealthStatus {
+    if healthy {
+        HealthStatus::Healthy
+    } else {
+        HealthStatus::Unhealthy
+    }
+}
+
+/// turns the database probe into the readiness report, unready when any required check fails
+/// or the service is shutting down
+pub(crate) fn readiness(
+    health: DatabaseHealth,
+    shutting_down: bool,
+) -> (StatusCode, api_models::HealthResponse) {
+    let reachable = health.error.is_none();
+    let migrations_error = match &health.pending_migrations {
+        None => Some("database is unreachable".to_string()),
+        Some(pending) if !pending.is_empty() => Some(format!(
+            "{} migrations have not been applied",
+            pending.len()
+        )),
+        Some(_) => None,
+    };
+    let mut checks = vec![
+        api_models::DependencyCheck {
+            name: "database".to_string(),
+            status: status_of(reachable),
+            required: true,
+            error: health.error,
+            details: json!({ "latency_ms": health.latency.as_millis() as u64 }),
+        },
+        api_models::DependencyCheck {
+            name: "migrations".to_string(),
+            status: status_of(migrations_error.is_none()),
+            required: true,
+            error: migrations_error,
+            details: json!({ "pending": health.pending_migrations }),
+        },
+        api_models::DependencyCheck {
+            name: "connection_pool".to_string(),
+            status: HealthStatus::Healthy,
+            required: false,
+            error: None,
+            details: json!({
+                "connections": health.pool.connections,
+                "idle_connections": health.pool.idle_connections,
+                "max_size": health.pool.max_size,
+            }),
+        },
+    ];
+    if shutting_down {
+        checks.push(api_models::DependencyCheck {
+            name: "shutdown".to_string(),
+            status: HealthStatus::Unhealthy,
+            required: true,
+            error: Some("service is shutting down".to_string()),
+            details: serde_json::Value::Null,
+        });
+    }
+
+    let ready = checks
+        .iter()
+        .all(|c| !c.required || c.status == HealthStatus::Healthy);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        api_models::HealthResponse {
+            status: status_of(ready),
+            checks,
+        },
+    )
+}
+
+/// the original liveness route, kept for the probes still pointing at it
+#[utoipa::path(
+    get,
+    path = "/health_check",
+    tag = "operations",
+    responses((status = 200, description = "the service is up", body = api_models::HealthResponse))
+)]
+pub async fn health_check_handler() -> Response {
+    live_handler().await
+}
+
+/// the process is up and serving requests; never touches a dependency
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "operations",
+    responses((status = 200, description = "the service is up", body = api_models::HealthResponse))
+)]
+pub async fn live_handler() -> Response {
+    json_response(
+        StatusCode::OK,
+        &api_models::HealthResponse {
+            status: HealthStatus::Healthy,
+            checks: Vec::new(),
+        },
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "operations",
+    responses(
+        (status = 200, description = "ready to serve traffic", body = api_models::HealthResponse),
+        (status = 503, description = "a required dependency is down or the service is shutting down", body = api_models::HealthResponse),
+    )
+)]
+pub async fn ready_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+) -> Response {
+    let health = app.health_repo.database_health().await;
+    let (status, health) = readiness(health, app.shutdown.is_triggered());
+    json_response(status, &health)
 }
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::adapter::repository::{DatabaseHealth, PoolStatistics};
+    use crate::model::models::HealthStatus;
+    use crate::routes::health_check::readiness;
+    use axum::http::StatusCode;
+
+    fn healthy_database() -> DatabaseHealth {
+        DatabaseHealth {
+            error: None,
+            latency: Duration::from_millis(3),
+            pool: PoolStatistics {
+                connections: 2,
+                idle_connections: 1,
+                max_size: 10,
+            },
+            pending_migrations: Some(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn ready_when_database_is_up_to_date() {
+        // act
+        let (status, health) = readiness(healthy_database(), false);
+
+        // assert
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(HealthStatus::Healthy, health.status);
+        let names: Vec<&str> = health.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(vec!["database", "migrations", "connection_pool"], names);
+        assert_eq!(10, health.checks[2].details["max_size"]);
+    }
+
+    #[test]
+    fn unready_when_database_is_unreachable() {
+        // arrange
+        let database = DatabaseHealth {
+            error: Some("timed out after 2s".to_string()),
+            pending_migrations: None,
+            ..healthy_database()
+        };
+
+        // act
+        let (status, health) = readiness(database, false);
+
+        // assert
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status);
+        assert_eq!(HealthStatus::Unhealthy, health.status);
+        assert_eq!(HealthStatus::Unhealthy, health.checks[0].status);
+        assert_eq!(
+            Some("timed out after 2s"),
+            health.checks[0].error.as_deref()
+        );
+        assert_eq!(HealthStatus::Unhealthy, health.checks[1].status);
+    }
+
+    #[test]
+    fn unready_with_pending_migrations() {
+        // arrange
+        let database = DatabaseHealth {
+            pending_migrations: Some(vec!["2026-10-18-130000_unique_subscription_email".into()]),
+            ..healthy_database()
+        };
+
+        // act
+        let (status, health) = readiness(database, false);
+
+        // assert
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status);
+        assert_eq!(HealthStatus::Healthy, health.checks[0].status);
+        assert_eq!(HealthStatus::Unhealthy, health.checks[1].status);
+    }
+
+    #[test]
+    fn unready_while_shutting_down() {
+        // act
+        let (status, health) = readiness(healthy_database(), true);
+
+        // assert
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status);
+        assert_eq!(HealthStatus::Unhealthy, health.status);
+        let shutdown = health.checks.iter().find(|c| c.name == "shutdown").unwrap();
+        assert!(shutdown.required);
+        assert_eq!(HealthStatus::Unhealthy, shutdown.status);
+    }
+}
@@ -0,0 +1,182 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::adapter::configuration::IdempotencyConfiguration;
+use crate::adapter::repository::IdempotencyRepository;
+use crate::api::REQUEST_ID_HEADER;
+use crate::domain::errors::{self as domain_errors, DomainError};
+use crate::domain::idempotency::{
+    self, Caller, IdempotencyKey, IdempotentRequest, Reservation, StoredResponse,
+};
+use crate::routes::rate_limit;
+use axum::body::{self, Body};
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderName, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+/// where responses to requests with an `Idempotency-Key` are kept, and for how long
+#[derive(Clone)]
+pub struct Idempotency {
+    pub repo: Arc<dyn IdempotencyRepository>,
+    pub cfg: IdempotencyConfiguration,
+}
+
+/// marks a response as the replay of one stored for an earlier request
+pub const REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// request bodies are read whole to compare retries, so larger ones are refused
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// answers a retried mutating request that carries an `Idempotency-Key` with the response
+/// to the first one, so clients can retry on timeouts without applying a change twice
+pub async fn replay(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(Idempotency { repo, cfg }) = &app.idempotency else {
+        return next.run(request).await;
+    };
+    if request.method().is_safe() {
+        return next.run(request).await;
+    }
+    let key = match request.headers().get(idempotency::HEADER) {
+        Some(value) => match IdempotencyKey::parse(value.to_str().unwrap_or_default()) {
+            Ok(key) => key,
+            Err(err) => return domain_errors::error_to_response(err).into_response(),
+        },
+        None => return next.run(request).await,
+    };
+
+    let (parts, body) = request.into_parts();
+    let caller = match parts.headers.get(AUTHORIZATION) {
+        Some(authorization) => Caller::Authorized(authorization.as_bytes()),
+        None => {
+            let peer = parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip());
+            let hops = app.rate_limiter.trusted_proxy_hops();
+            match rate_limit::client_ip(&parts.headers, peer, hops) {
+                Some(ip) => Caller::Anonymous(ip),
+                None => {
+                    // anonymous callers without an address would all share one set of keys
+                    tracing::debug!("not deduplicating a request without a client address");
+                    return next.run(Request::from_parts(parts, body)).await;
+                }
+            }
+        }
+    };
+    let body = match body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return domain_errors::error_to_response(DomainError::Validation {
+                field: "body".to_string(),
+                message: format!("must be at most {} bytes", MAX_BODY_BYTES),
+            })
+            .into_response()
+        }
+    };
+    let idempotent = IdempotentRequest::new(
+        &key,
+        caller,
+        parts.method.as_str(),
+        parts
+            .uri
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or_default(),
+        &body,
+    );
+
+    let reservation = repo
+        .reserve_idempotency_key(
+            idempotent.clone(),
+            SystemTime::now(),
+            cfg.ttl,
+            cfg.in_progress_timeout,
+        )
+        .await;
+    match reservation {
+        Ok(Reservation::Started) => {}
+        Ok(Reservation::Completed(stored)) => return replayed(stored),
+        Ok(Reservation::InProgress) => {
+            return domain_errors::error_to_response(DomainError::Conflict(
+                "a request with this Idempotency-Key is still being processed".to_string(),
+            ))
+            .into_response()
+        }
+        Ok(Reservation::PayloadMismatch) => {
+            return domain_errors::error_to_response(DomainError::Unprocessable(
+                "Idempotency-Key was already used for a different request".to_string(),
+            ))
+            .into_response()
+        }
+        Err(err) => {
+            tracing::warn!("failed to reserve idempotency key: {}", err);
+            return domain_errors::error_to_response(err).into_response();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            let _ = repo.release_idempotency_key(idempotent).await;
+            return domain_errors::error_to_response(DomainError::Internal(format!(
+                "failed to read response body: {}",
+                err
+            )))
+            .into_response();
+        }
+    };
+
+    let status = parts.status.as_u16();
+    let stored = if idempotency::is_replayable(status) {
+        // a replay is a new request, the id of the first one would tie it to the wrong logs
+        let headers = parts
+            .headers
+            .iter()
+            .filter(|(name, _)| name.as_str() != REQUEST_ID_HEADER)
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        repo.complete_idempotency_key(
+            idempotent.clone(),
+            StoredResponse {
+                status,
+                headers,
+                body: body.to_vec(),
+            },
+        )
+        .await
+    } else {
+        repo.release_idempotency_key(idempotent.clone()).await
+    };
+    if let Err(err) = stored {
+        // the caller still gets its answer, a retry just runs the request again
+        tracing::warn!("failed to store response for idempotency key: {}", err);
+        let _ = repo.release_idempotency_key(idempotent).await;
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replayed(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
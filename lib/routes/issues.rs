@@ -0,0 +1,166 @@
+use super::auth::{Authorized, Publisher, ReadOnly};
+use super::response::to_response;
+use crate::adapter::repository::{IssueRepository, JobRepository};
+use crate::domain::errors::DomainError;
+use crate::domain::jobs::Job;
+use crate::model::models::{self as api_models, ErrorResponse};
+use axum::extract::Path;
+use axum::{http::StatusCode, Extension, Json};
+use std::str::FromStr;
+use std::{sync::Arc, time::SystemTime};
+use uuid::Uuid;
+
+fn parse_id(field: &str, id: &str) -> Result<Uuid, DomainError> {
+    Uuid::from_str(id).map_err(|_| DomainError::Validation {
+        field: field.to_string(),
+        message: "Id must be a uuid".to_string(),
+    })
+}
+
+async fn create_issue(
+    newsletter_id: String,
+    req: api_models::CreateIssueRequest,
+    repo: &dyn IssueRepository,
+) -> Result<api_models::Issue, DomainError> {
+    let newsletter_id = parse_id("newsletter_id", &newsletter_id)?;
+    if req.title.trim().is_empty() {
+        return Err(DomainError::Validation {
+            field: "title".to_string(),
+            message: "title must not be empty".to_string(),
+        });
+    }
+    repo.add_issue(
+        newsletter_id,
+        req.title,
+        req.text_body,
+        req.html_body,
+        SystemTime::now(),
+    )
+    .await
+}
+
+async fn get_issue(
+    newsletter_id: String,
+    issue_id: String,
+    repo: &dyn IssueRepository,
+) -> Result<api_models::IssueResponse, DomainError> {
+    let newsletter_id = parse_id("newsletter_id", &newsletter_id)?;
+    let issue_id = parse_id("issue_id", &issue_id)?;
+    let issue = repo.get_issue(newsletter_id, issue_id).await?;
+    let deliveries = repo.delivery_summary(issue_id).await?;
+    Ok(api_models::IssueResponse { issue, deliveries })
+}
+
+/// queues the fan-out to every confirmed subscriber; publishing again resumes an
+/// interrupted publish and retries failed deliveries without resending sent ones
+async fn publish_issue(
+    newsletter_id: String,
+    issue_id: String,
+    repo: &dyn IssueRepository,
+    job_repo: &dyn JobRepository,
+    max_attempts: i32,
+) -> Result<api_models::IssueResponse, DomainError> {
+    let newsletter_id = parse_id("newsletter_id", &newsletter_id)?;
+    let issue_id = parse_id("issue_id", &issue_id)?;
+    let issue = repo.get_issue(newsletter_id, issue_id).await?;
+
+    let job = Job::PublishIssue {
+        newsletter_id,
+        issue_id,
+    };
+    job_repo
+        .enqueue_job(&job, max_attempts, SystemTime::now())
+        .await?;
+
+    let deliveries = repo.delivery_summary(issue_id).await?;
+    Ok(api_models::IssueResponse { issue, deliveries })
+}
+
+#[utoipa::path(
+    post,
+    path = "/newsletters/{newsletter_id}/issues",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter")),
+    request_body = api_models::CreateIssueRequest,
+    security(("api_key" = [])),
+    responses(
+        (status = 201, description = "issue created", body = api_models::Issue),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "newsletter not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_issue_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<Publisher>,
+    Path(newsletter_id): Path<String>,
+    arg: Json<api_models::CreateIssueRequest>,
+) -> axum::response::Response {
+    let repo = app.issue_repo.clone();
+    to_response(
+        StatusCode::CREATED,
+        create_issue(newsletter_id, arg.0, repo.as_ref()).await,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/newsletters/{newsletter_id}/issues/{issue_id}",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter"), ("issue_id" = String, Path, description = "uuid of the issue")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the issue and its deliveries", body = api_models::IssueResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "issue not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_issue_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<ReadOnly>,
+    Path((newsletter_id, issue_id)): Path<(String, String)>,
+) -> axum::response::Response {
+    let repo = app.issue_repo.clone();
+    to_response(
+        StatusCode::OK,
+        get_issue(newsletter_id, issue_id, repo.as_ref()).await,
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/newsletters/{newsletter_id}/issues/{issue_id}/publish",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter"), ("issue_id" = String, Path, description = "uuid of the issue")),
+    security(("api_key" = [])),
+    responses(
+        (status = 202, description = "delivery to every confirmed subscriber queued", body = api_models::IssueResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "issue not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn publish_issue_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<Publisher>,
+    Path((newsletter_id, issue_id)): Path<(String, String)>,
+) -> axum::response::Response {
+    let repo = app.issue_repo.clone();
+    let job_repo = app.job_repo.clone();
+    let res = publish_issue(
+        newsletter_id,
+        issue_id,
+        repo.as_ref(),
+        job_repo.as_ref(),
+        app.worker_cfg.max_attempts,
+    )
+    .await;
+    if let Ok(resp) = &res {
+        tracing::info!("queued publish of issue {}", resp.issue.issue_id);
+    }
+    to_response(StatusCode::ACCEPTED, res)
+}
@@ -0,0 +1,67 @@
+use super::auth::{Admin, Authorized, ReadOnly};
+use super::response::to_response;
+use crate::domain::errors::DomainError;
+use crate::model::models::{self as api_models, ErrorResponse};
+use axum::extract::Path;
+use axum::{http::StatusCode, Extension};
+use std::str::FromStr;
+use std::{sync::Arc, time::SystemTime};
+use uuid::Uuid;
+
+fn parse_job_id(id: &str) -> Result<Uuid, DomainError> {
+    Uuid::from_str(id).map_err(|_| DomainError::Validation {
+        field: "job_id".to_string(),
+        message: "Id must be a uuid".to_string(),
+    })
+}
+
+/// lists jobs that exhausted their attempts so an operator can inspect or requeue them
+#[utoipa::path(
+    get,
+    path = "/jobs/dead",
+    tag = "jobs",
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "jobs that failed on every attempt", body = api_models::GetJobsResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_dead_jobs_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<ReadOnly>,
+) -> axum::response::Response {
+    let repo = app.job_repo.clone();
+    let res = repo
+        .get_jobs(api_models::JobStatus::Dead)
+        .await
+        .map(|resp| api_models::GetJobsResponse { resp });
+    to_response(StatusCode::OK, res)
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{job_id}/requeue",
+    tag = "jobs",
+    params(("job_id" = String, Path, description = "uuid of the dead job")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the requeued job", body = api_models::Job),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "no dead job with the id", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn requeue_job_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<Admin>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let repo = app.job_repo.clone();
+    let res = match parse_job_id(&id) {
+        Ok(id) => repo.requeue_job(id, SystemTime::now()).await,
+        Err(err) => Err(err),
+    };
+    to_response(StatusCode::OK, res)
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::domain::errors::ErrorVariant;
+use axum::extract::{MatchedPath, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+/// serves every collector in the Prometheus text format, refreshing the pool gauges first
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "operations",
+    responses((status = 200, description = "Prometheus text format", body = String, content_type = "text/plain"))
+)]
+pub async fn handler(Extension(app): axum::Extension<Arc<super::app::Application>>) -> Response {
+    app.metrics.record_pool(&app.health_repo.pool_statistics());
+    (
+        StatusCode::OK,
+        [(CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        app.metrics.render(),
+    )
+        .into_response()
+}
+
+/// counts and times every request by route template rather than raw uri, so path
+/// parameters do not blow up the label set
+pub async fn track_requests(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    app.metrics.observe_request(
+        &method,
+        &path,
+        response.status().as_u16(),
+        started.elapsed(),
+    );
+    if let Some(ErrorVariant(variant)) = response.extensions().get::<ErrorVariant>() {
+        app.metrics.domain_error(variant);
+    }
+    response
+}
@@ -1,4 +1,19 @@
 pub mod app;
+pub(crate) mod auth;
 pub(crate) mod echo;
 pub(crate) mod health_check;
+pub(super) mod health_check_test;
+pub(crate) mod idempotency;
+pub(crate) mod issues;
+pub(crate) mod jobs;
+pub(crate) mod metrics;
+pub(crate) mod newsletters;
+pub(crate) mod openapi;
+pub(super) mod openapi_test;
+pub(crate) mod rate_limit;
+pub(super) mod rate_limit_test;
+pub(crate) mod request_id;
+pub(crate) mod response;
+pub(crate) mod route_table;
 pub(crate) mod subscriptions;
+pub(super) mod subscriptions_test;
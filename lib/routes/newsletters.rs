@@ -0,0 +1,178 @@
+use super::auth::{Authorized, Publisher};
+use super::response::to_response;
+use crate::adapter::repository::NewsletterRepository;
+use crate::domain::errors::DomainError;
+use crate::model::models::{self as api_models, ErrorResponse};
+use axum::extract::Path;
+use axum::{http::StatusCode, Extension, Json};
+use std::str::FromStr;
+use std::{sync::Arc, time::SystemTime};
+use uuid::Uuid;
+
+fn parse_newsletter_id(id: &str) -> Result<Uuid, DomainError> {
+    Uuid::from_str(id).map_err(|_| DomainError::Validation {
+        field: "newsletter_id".to_string(),
+        message: "Id must be a uuid".to_string(),
+    })
+}
+
+fn validate_name(name: &str) -> Result<String, DomainError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DomainError::Validation {
+            field: "name".to_string(),
+            message: "name must not be empty".to_string(),
+        });
+    }
+    Ok(name.to_string())
+}
+
+async fn create_newsletter(
+    req: api_models::CreateNewsletterRequest,
+    repo: &dyn NewsletterRepository,
+) -> Result<api_models::Newsletter, DomainError> {
+    let name = validate_name(&req.name)?;
+    repo.add_newsletter(name, req.description, SystemTime::now())
+        .await
+}
+
+async fn update_newsletter(
+    id: String,
+    req: api_models::UpdateNewsletterRequest,
+    repo: &dyn NewsletterRepository,
+) -> Result<api_models::Newsletter, DomainError> {
+    let id = parse_newsletter_id(&id)?;
+    let name = validate_name(&req.name)?;
+    repo.update_newsletter(id, name, req.description).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/newsletters",
+    tag = "newsletters",
+    request_body = api_models::CreateNewsletterRequest,
+    security(("api_key" = [])),
+    responses(
+        (status = 201, description = "newsletter created", body = api_models::Newsletter),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 409, description = "a newsletter with this name already exists", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_newsletter_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<Publisher>,
+    arg: Json<api_models::CreateNewsletterRequest>,
+) -> axum::response::Response {
+    let repo = app.newsletter_repo.clone();
+    to_response(
+        StatusCode::CREATED,
+        create_newsletter(arg.0, repo.as_ref()).await,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/newsletters",
+    tag = "newsletters",
+    responses((status = 200, description = "every newsletter", body = api_models::GetNewslettersResponse))
+)]
+pub(crate) async fn get_newsletters_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+) -> axum::response::Response {
+    let repo = app.newsletter_repo.clone();
+    let res = repo
+        .get_newsletters()
+        .await
+        .map(|resp| api_models::GetNewslettersResponse { resp });
+    to_response(StatusCode::OK, res)
+}
+
+#[utoipa::path(
+    get,
+    path = "/newsletters/{newsletter_id}",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter")),
+    responses(
+        (status = 200, description = "the newsletter", body = api_models::Newsletter),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 404, description = "newsletter not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_newsletter_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let repo = app.newsletter_repo.clone();
+    let res = match parse_newsletter_id(&id) {
+        Ok(id) => repo.get_newsletter(id).await,
+        Err(err) => Err(err),
+    };
+    to_response(StatusCode::OK, res)
+}
+
+#[utoipa::path(
+    put,
+    path = "/newsletters/{newsletter_id}",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter")),
+    request_body = api_models::UpdateNewsletterRequest,
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the updated newsletter", body = api_models::Newsletter),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "newsletter not found", body = ErrorResponse),
+        (status = 409, description = "another newsletter has this name", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn update_newsletter_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<Publisher>,
+    Path(id): Path<String>,
+    arg: Json<api_models::UpdateNewsletterRequest>,
+) -> axum::response::Response {
+    let repo = app.newsletter_repo.clone();
+    to_response(
+        StatusCode::OK,
+        update_newsletter(id, arg.0, repo.as_ref()).await,
+    )
+}
+
+#[utoipa::path(
+    delete,
+    path = "/newsletters/{newsletter_id}",
+    tag = "newsletters",
+    params(("newsletter_id" = String, Path, description = "uuid of the newsletter")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the removed newsletter", body = api_models::RemoveNewsletterResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "newsletter not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn remove_newsletter_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    auth: Authorized<Publisher>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let repo = app.newsletter_repo.clone();
+    let res = match parse_newsletter_id(&id) {
+        Ok(id) => repo.remove_newsletter(id).await,
+        Err(err) => Err(err),
+    }
+    .map(|newsletter| {
+        tracing::info!(
+            "newsletter {} removed with api key {} ({})",
+            newsletter.newsletter_id,
+            auth.api_key.name,
+            auth.api_key.api_key_id
+        );
+        api_models::RemoveNewsletterResponse { newsletter }
+    });
+    to_response(StatusCode::OK, res)
+}
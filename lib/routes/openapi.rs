@@ -0,0 +1,116 @@
+use axum::Json;
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::openapi::server::Server;
+use utoipa::{Modify, OpenApi};
+
+use crate::api::API_PREFIX;
+use crate::model::models as api_models;
+
+/// the OpenAPI document, generated from the `#[utoipa::path]` of every handler; its paths
+/// are relative to the `API_PREFIX` server, but for the ops routes served from the root
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "newsletter_service"),
+    paths(
+        super::echo::handler,
+        super::health_check::health_check_handler,
+        super::health_check::live_handler,
+        super::health_check::ready_handler,
+        super::metrics::handler,
+        handler,
+        super::subscriptions::create_subscription_handler,
+        super::subscriptions::remove_subscription_handler,
+        super::subscriptions::get_subscription_handler,
+        super::subscriptions::get_subscription_by_id_handler,
+        super::subscriptions::delete_subscription_handler,
+        super::subscriptions::confirm_subscription_handler,
+        super::subscriptions::unsubscribe_preview_handler,
+        super::subscriptions::unsubscribe_handler,
+        super::subscriptions::list_subscriptions_handler,
+        super::newsletters::create_newsletter_handler,
+        super::newsletters::get_newsletters_handler,
+        super::newsletters::get_newsletter_handler,
+        super::newsletters::update_newsletter_handler,
+        super::newsletters::remove_newsletter_handler,
+        super::issues::create_issue_handler,
+        super::issues::get_issue_handler,
+        super::issues::publish_issue_handler,
+        super::jobs::get_dead_jobs_handler,
+        super::jobs::requeue_job_handler,
+    ),
+    components(schemas(api_models::ErrorResponse)),
+    modifiers(&ApiKeyAuth, &Deprecated, &Servers),
+    tags(
+        (name = "subscriptions", description = "subscribing, confirming and unsubscribing"),
+        (name = "newsletters", description = "newsletters and their issues"),
+        (name = "jobs", description = "the background job queue"),
+        (name = "operations", description = "health, metrics and this document"),
+    )
+)]
+pub struct ApiDoc;
+
+/// the `api_key` scheme the handlers that need a key refer to
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "api_key",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+    }
+}
+
+/// marks the operations kept only for existing callers
+struct Deprecated;
+
+impl Modify for Deprecated {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(remove) = openapi
+            .paths
+            .paths
+            .get_mut("/subscribe")
+            .and_then(|item| item.delete.as_mut())
+        {
+            // replaced by DELETE /subscriptions/{subscription_id}
+            remove.deprecated = Some(utoipa::openapi::Deprecated::True);
+        }
+    }
+}
+
+/// serves the document from `API_PREFIX`, but for the ops routes, which are not versioned
+struct Servers;
+
+impl Modify for Servers {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi.servers = Some(vec![Server::new(API_PREFIX)]);
+        for (_, path) in crate::api::ops_route_table().routes() {
+            if let Some(item) = openapi.paths.paths.get_mut(*path) {
+                item.servers = Some(vec![Server::new("/")]);
+            }
+        }
+    }
+}
+
+/// serves the OpenAPI document
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "operations",
+    responses((status = 200, description = "this document", content_type = "application/json"))
+)]
+pub async fn handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI at `{API_PREFIX}/swagger-ui`, reading the document from
+/// `{API_PREFIX}/openapi.json`
+#[cfg(feature = "swagger-ui")]
+pub fn swagger_ui() -> utoipa_swagger_ui::SwaggerUi {
+    utoipa_swagger_ui::SwaggerUi::new(format!("{}/swagger-ui", API_PREFIX)).config(
+        utoipa_swagger_ui::Config::from(format!("{}/openapi.json", API_PREFIX)),
+    )
+}
@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::api::{ops_route_table, route_table, API_PREFIX};
+    use crate::routes::openapi::ApiDoc;
+    use utoipa::openapi::path::{Operation, ParameterIn};
+    use utoipa::OpenApi;
+
+    /// `/a/:id` the way OpenAPI writes it, `/a/{id}`
+    fn openapi_path(path: &str) -> String {
+        path.split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => format!("{{{}}}", name),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn operations() -> Vec<(String, String, Operation)> {
+        let spec = ApiDoc::openapi();
+        spec.paths
+            .paths
+            .into_iter()
+            .flat_map(|(path, item)| {
+                [
+                    ("GET", item.get),
+                    ("POST", item.post),
+                    ("PUT", item.put),
+                    ("PATCH", item.patch),
+                    ("DELETE", item.delete),
+                ]
+                .into_iter()
+                .filter_map(move |(method, op)| op.map(|op| (method.to_string(), path.clone(), op)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        // arrange
+        let (api, ops) = (route_table(), ops_route_table());
+        let routed: BTreeSet<(String, String)> = api
+            .routes()
+            .iter()
+            .chain(ops.routes())
+            .map(|(method, path)| (method.to_string(), openapi_path(path)))
+            .collect();
+
+        // act
+        let documented: BTreeSet<(String, String)> = operations()
+            .into_iter()
+            .map(|(method, path, _)| (method, path))
+            .collect();
+
+        // assert
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let unrouted: Vec<_> = documented.difference(&routed).collect();
+        assert!(
+            undocumented.is_empty(),
+            "routes missing from the OpenAPI document: {:?}",
+            undocumented
+        );
+        assert!(
+            unrouted.is_empty(),
+            "documented operations that are not routed: {:?}",
+            unrouted
+        );
+    }
+
+    #[test]
+    fn api_routes_are_served_from_the_prefix_and_ops_routes_from_the_root() {
+        // arrange
+        let spec = ApiDoc::openapi();
+
+        // act
+        let servers: Vec<(&str, Option<String>)> = ops_route_table()
+            .routes()
+            .iter()
+            .map(|(_, path)| {
+                let item = &spec.paths.paths[*path];
+                let url = item.servers.as_ref().map(|servers| servers[0].url.clone());
+                (*path, url)
+            })
+            .collect();
+
+        // assert
+        for (path, url) in servers {
+            assert_eq!(Some("/".to_string()), url, "{}", path);
+        }
+        let subscribe = &spec.paths.paths["/subscribe"];
+        assert!(subscribe.servers.is_none());
+        assert_eq!(API_PREFIX, spec.servers.unwrap()[0].url);
+    }
+
+    #[test]
+    fn path_parameters_are_declared() {
+        for (method, path, op) in operations() {
+            let declared: BTreeSet<String> = op
+                .parameters
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|param| matches!(param.parameter_in, ParameterIn::Path))
+                .map(|param| param.name)
+                .collect();
+            let in_path: BTreeSet<String> = path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                .map(str::to_string)
+                .collect();
+            assert_eq!(in_path, declared, "{} {}", method, path);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::adapter::configuration::RateLimitConfiguration;
+use crate::api::API_PREFIX;
+use crate::domain::errors::{self as domain_errors, DomainError};
+use crate::domain::rate_limit::TokenBuckets;
+use axum::extract::{ConnectInfo, MatchedPath, Request};
+use axum::http::{HeaderMap, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// the buckets behind every configured limit; routes without a quota are not limited
+pub struct RateLimiter {
+    trusted_proxy_hops: usize,
+    subscribe_per_ip: Option<TokenBuckets>,
+    subscribe_per_email: Option<TokenBuckets>,
+    confirm_per_ip: Option<TokenBuckets>,
+    unsubscribe_per_ip: Option<TokenBuckets>,
+}
+
+impl RateLimiter {
+    pub fn new(cfg: &RateLimitConfiguration) -> Self {
+        Self {
+            trusted_proxy_hops: cfg.trusted_proxy_hops,
+            subscribe_per_ip: cfg.subscribe.per_ip.map(TokenBuckets::new),
+            subscribe_per_email: cfg.subscribe.per_email.map(TokenBuckets::new),
+            confirm_per_ip: cfg.confirm.per_ip.map(TokenBuckets::new),
+            unsubscribe_per_ip: cfg.unsubscribe.per_ip.map(TokenBuckets::new),
+        }
+    }
+
+    /// the per-client buckets of a route, by its template under `API_PREFIX` or on its
+    /// unprefixed alias
+    pub(crate) fn per_ip(&self, method: &Method, path: &str) -> Option<&TokenBuckets> {
+        match (method, path.strip_prefix(API_PREFIX).unwrap_or(path)) {
+            (&Method::POST, "/subscribe") => self.subscribe_per_ip.as_ref(),
+            (_, "/subscriptions/confirm") => self.confirm_per_ip.as_ref(),
+            (_, "/unsubscribe") => self.unsubscribe_per_ip.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// how many proxies in front of the service append to `X-Forwarded-For`
+    pub(crate) fn trusted_proxy_hops(&self) -> usize {
+        self.trusted_proxy_hops
+    }
+
+    /// takes a token for subscribing `email`, whatever client asks for it
+    pub fn check_email(&self, email: &str) -> Result<(), DomainError> {
+        match &self.subscribe_per_email {
+            Some(buckets) => buckets
+                .acquire(&email.to_lowercase(), Instant::now())
+                .map_err(|retry_after| DomainError::RateLimited { retry_after }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    /// limits nothing
+    fn default() -> Self {
+        Self::new(&RateLimitConfiguration::default())
+    }
+}
+
+/// the address of the client; behind `trusted_proxy_hops` proxies, the one the outermost
+/// of them appended to `X-Forwarded-For`, since anything before it is up to the client
+pub(crate) fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxy_hops: usize,
+) -> Option<IpAddr> {
+    if trusted_proxy_hops == 0 {
+        return peer;
+    }
+    let forwarded: Vec<&str> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    forwarded
+        .len()
+        .checked_sub(trusted_proxy_hops)
+        .and_then(|index| {
+            let entry = forwarded[index];
+            entry
+                .parse::<IpAddr>()
+                .or_else(|_| entry.parse::<SocketAddr>().map(|addr| addr.ip()))
+                .ok()
+        })
+        .or(peer)
+}
+
+/// answers 429 once a client used up the quota of the route it calls
+pub async fn limit_by_ip(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = &app.rate_limiter;
+    let buckets = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| limiter.per_ip(request.method(), path.as_str()));
+    if let Some(buckets) = buckets {
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        match client_ip(request.headers(), peer, limiter.trusted_proxy_hops) {
+            Some(ip) => {
+                if let Err(retry_after) = buckets.acquire(&ip.to_string(), Instant::now()) {
+                    tracing::info!("rate limited {}", ip);
+                    return domain_errors::error_to_response(DomainError::RateLimited {
+                        retry_after,
+                    })
+                    .into_response();
+                }
+            }
+            None => tracing::debug!("not rate limiting a request without a client address"),
+        }
+    }
+    next.run(request).await
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+    use std::time::Duration;
+
+    use crate::adapter::configuration::{Quota, RateLimitConfiguration};
+    use crate::api::API_PREFIX;
+    use crate::domain::errors::DomainError;
+    use crate::routes::rate_limit::{client_ip, RateLimiter};
+    use axum::http::{HeaderMap, HeaderValue, Method};
+
+    fn peer() -> Option<IpAddr> {
+        Some("10.0.0.1".parse().unwrap())
+    }
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn client_ip_ignores_forwarded_for_without_trusted_proxies() {
+        // arrange
+        let headers = forwarded_for(&["203.0.113.7"]);
+
+        // act
+        let ip = client_ip(&headers, peer(), 0);
+
+        // assert
+        assert_eq!(peer(), ip);
+    }
+
+    #[test]
+    fn client_ip_takes_the_entry_appended_by_the_outermost_proxy() {
+        // arrange
+        let headers = forwarded_for(&["198.51.100.1, 203.0.113.7", "192.0.2.5"]);
+
+        // act
+        let one_hop = client_ip(&headers, peer(), 1);
+        let two_hops = client_ip(&headers, peer(), 2);
+
+        // assert
+        assert_eq!(Some("192.0.2.5".parse().unwrap()), one_hop);
+        assert_eq!(Some("203.0.113.7".parse().unwrap()), two_hops);
+    }
+
+    #[test]
+    fn client_ip_falls_back_to_the_peer() {
+        // arrange
+        let short = forwarded_for(&["203.0.113.7"]);
+        let garbage = forwarded_for(&["unknown"]);
+
+        // act
+        let too_few_entries = client_ip(&short, peer(), 2);
+        let unparsable = client_ip(&garbage, peer(), 1);
+
+        // assert
+        assert_eq!(peer(), too_few_entries);
+        assert_eq!(peer(), unparsable);
+    }
+
+    #[test]
+    fn check_email_ignores_case() {
+        // arrange
+        let mut cfg = RateLimitConfiguration::default();
+        cfg.subscribe.per_email = Some(Quota {
+            capacity: 1,
+            refill: Duration::from_secs(60),
+        });
+        let limiter = RateLimiter::new(&cfg);
+
+        // act
+        let first = limiter.check_email("someone@example.com");
+        let second = limiter.check_email("Someone@Example.com");
+
+        // assert
+        assert!(first.is_ok());
+        assert!(matches!(second, Err(DomainError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn per_ip_matches_routes_with_or_without_the_api_prefix() {
+        // arrange
+        let quota = Quota {
+            capacity: 1,
+            refill: Duration::from_secs(60),
+        };
+        let mut cfg = RateLimitConfiguration::default();
+        cfg.subscribe.per_ip = Some(quota);
+        cfg.confirm.per_ip = Some(quota);
+        cfg.unsubscribe.per_ip = Some(quota);
+        let limiter = RateLimiter::new(&cfg);
+        let subscribe = format!("{}/subscribe", API_PREFIX);
+
+        // act
+        let post_subscribe = limiter.per_ip(&Method::POST, &subscribe);
+        let get_subscribe = limiter.per_ip(&Method::GET, &subscribe);
+        let confirm = limiter.per_ip(
+            &Method::GET,
+            &format!("{}/subscriptions/confirm", API_PREFIX),
+        );
+        let unsubscribe = limiter.per_ip(&Method::POST, &format!("{}/unsubscribe", API_PREFIX));
+        let unprefixed = limiter.per_ip(&Method::POST, "/subscribe");
+        let ops = limiter.per_ip(&Method::GET, "/health_check");
+
+        // assert
+        assert!(post_subscribe.is_some());
+        assert!(get_subscribe.is_none());
+        assert!(confirm.is_some());
+        assert!(unsubscribe.is_some());
+        assert!(unprefixed.is_some());
+        assert!(ops.is_none());
+    }
+}
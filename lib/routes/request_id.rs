@@ -0,0 +1,45 @@
+use crate::domain::errors::ErrorVariant;
+use crate::model::models::ErrorResponse;
+use axum::body::{self, Body};
+use axum::extract::Request;
+use axum::http::header::CONTENT_LENGTH;
+use axum::middleware::Next;
+use axum::response::Response;
+use tower_http::request_id::RequestId;
+
+/// fills the `request_id` of domain error bodies with the id `SetRequestIdLayer` attached,
+/// so a client can quote the id that finds the matching log lines
+pub async fn scope(request: Request, next: Next) -> Response {
+    let id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(str::to_string);
+    let response = next.run(request).await;
+    match id {
+        Some(id) if response.extensions().get::<ErrorVariant>().is_some() => {
+            with_request_id(response, id).await
+        }
+        _ => response,
+    }
+}
+
+async fn with_request_id(response: Response, id: String) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            tracing::warn!("failed to read error body: {}", err);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let body = match serde_json::from_slice::<ErrorResponse>(&bytes) {
+        Ok(mut error) => {
+            error.request_id = Some(id);
+            serde_json::to_vec(&error).map_or_else(|_| Body::from(bytes), Body::from)
+        }
+        Err(_) => Body::from(bytes),
+    };
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, body)
+}
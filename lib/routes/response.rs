@@ -0,0 +1,51 @@
+use crate::api::API_PREFIX;
+use crate::domain::errors::{self as domain_errors, DomainError};
+use axum::extract::Request;
+use axum::http::header::{CONTENT_TYPE, LINK};
+use axum::http::{HeaderValue, Response, StatusCode};
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use serde::Serialize;
+
+/// serializes `res` as a json body with `status`, or the domain error response
+pub(crate) fn to_response<T: Serialize>(
+    status: StatusCode,
+    res: Result<T, DomainError>,
+) -> axum::response::Response {
+    match res {
+        Ok(t) => {
+            let json_body = serde_json::to_string(&t).unwrap_or_else(|_| "{}".to_string());
+            Response::builder()
+                .status(status)
+                .header(CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(json_body))
+                .unwrap()
+        }
+        Err(e) => domain_errors::error_to_response(e).into_response(),
+    }
+}
+
+/// flags `response` as served by a deprecated route and links to the route replacing it
+pub(crate) fn deprecated(
+    mut response: axum::response::Response,
+    successor: &str,
+) -> axum::response::Response {
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+    {
+        headers.insert(LINK, link);
+    }
+    response
+}
+
+/// flags responses of the unprefixed api routes as deprecated in favour of the same route
+/// under `API_PREFIX`, unless the route is deprecated with a successor of its own
+pub(crate) async fn unversioned_alias(request: Request, next: Next) -> axum::response::Response {
+    let successor = format!("{}{}", API_PREFIX, request.uri().path());
+    let response = next.run(request).await;
+    if response.headers().contains_key("deprecation") {
+        return response;
+    }
+    deprecated(response, &successor)
+}
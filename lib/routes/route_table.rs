@@ -0,0 +1,35 @@
+use axum::handler::Handler;
+use axum::http::Method;
+use axum::routing::{on, MethodFilter};
+use axum::Router;
+
+/// a router that remembers the method and path of every route added to it, so a test can
+/// check each one is in the OpenAPI document
+#[derive(Default)]
+pub struct RouteTable {
+    router: Router,
+    routes: Vec<(Method, &'static str)>,
+}
+
+impl RouteTable {
+    /// serves `method` on `path` with `handler`; the methods of a path may be added apart
+    pub fn route<H, T>(mut self, method: Method, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, ()>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone())
+            .unwrap_or_else(|err| panic!("cannot route {} {}: {}", method, path, err));
+        self.router = self.router.route(path, on(filter, handler));
+        self.routes.push((method, path));
+        self
+    }
+
+    pub fn routes(&self) -> &[(Method, &'static str)] {
+        &self.routes
+    }
+
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+}
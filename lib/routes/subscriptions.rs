@@ -1,18 +1,37 @@
-use crate::domain::errors::{self as domain_errors, DomainError};
-use crate::model::models::{self as api_models};
-use axum::http::header::CONTENT_TYPE;
-use axum::http::Response;
+use super::auth::{Admin, Authorized, MaybeAuthorized, ReadOnly};
+use super::rate_limit::RateLimiter;
+use super::response::{deprecated, to_response};
+use crate::adapter::configuration::SubscriptionConfiguration;
+use crate::adapter::repository::{
+    JobRepository, NewsletterRepository, SubscriptionCursor, SubscriptionFilter,
+    SubscriptionRepository,
+};
+use crate::api::API_PREFIX;
+use crate::domain::errors::{self as domain_errors, DomainError, ErrorVariant};
+use crate::domain::jobs::Job;
+use crate::domain::subscriber::{SubscriberEmail, SubscriberName};
+use crate::domain::unsubscribe_token;
+use crate::model::models::{self as api_models, ErrorResponse};
+use axum::body::Bytes;
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderValue, Uri};
 use axum::response::IntoResponse;
 use axum::{http::StatusCode, Extension, Json};
 use std::str::FromStr;
 use std::{sync::Arc, time::SystemTime};
 use uuid::Uuid;
 
-fn get_subscriptions(
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 200;
+
+async fn get_subscriptions(
     req: api_models::GetSubscriptionRequest,
-    repo: &mut (dyn crate::adapter::repository::SubscriptionRepository + Send + Sync),
+    repo: &dyn SubscriptionRepository,
 ) -> Result<api_models::GetSubscriptionsResponse, domain_errors::DomainError> {
-    let resp = repo.get_subscriptions(req.email.clone());
+    let resp = repo
+        .get_subscriptions(req.email.clone(), req.include_pending)
+        .await;
     if resp.is_empty() {
         Err(DomainError::NotFound(format!(
             "no subscriptions found for email: {}",
@@ -23,90 +42,570 @@ fn get_subscriptions(
     }
 }
 
-fn remove_subscription(
-    req: api_models::RemoveSubscriptionRequest,
-    repo: &mut (dyn crate::adapter::repository::SubscriptionRepository + Send + Sync),
+fn parse_subscription_id(id: &str) -> Result<Uuid, DomainError> {
+    Uuid::from_str(id).map_err(|_| DomainError::Validation {
+        field: "subscription_id".to_string(),
+        message: "Id must be a uuid".to_string(),
+    })
+}
+
+async fn remove_subscription(
+    id: &str,
+    removed_by: &api_models::ApiKey,
+    repo: &dyn SubscriptionRepository,
 ) -> Result<api_models::RemoveSubscriptionResponse, DomainError> {
-    let id = Uuid::from_str(req.subscription_id.as_str());
-    if id.is_err() {
+    let id = parse_subscription_id(id)?;
+    let subscription = repo.remove_subscription(id).await?;
+    tracing::info!(
+        "subscription {} removed with api key {} ({})",
+        id,
+        removed_by.name,
+        removed_by.api_key_id
+    );
+    Ok(api_models::RemoveSubscriptionResponse { subscription })
+}
+
+/// `{seconds}.{nanoseconds}.{id}` of the last subscription on a page
+fn encode_cursor(cursor: &SubscriptionCursor) -> String {
+    format!(
+        "{}.{:09}.{}",
+        cursor.subscribed_at.timestamp(),
+        cursor.subscribed_at.timestamp_subsec_nanos(),
+        cursor.id.simple()
+    )
+}
+
+fn cursor_after(sub: &api_models::Subscription) -> Result<String, DomainError> {
+    let id = Uuid::from_str(&sub.subscription_id)
+        .map_err(|err| DomainError::Internal(format!("invalid subscription id: {}", err)))?;
+    Ok(encode_cursor(&SubscriptionCursor {
+        subscribed_at: sub.subscribe_since,
+        id,
+    }))
+}
+
+fn decode_cursor(cursor: &str) -> Result<SubscriptionCursor, DomainError> {
+    let invalid = || DomainError::Validation {
+        field: "cursor".to_string(),
+        message: "cursor must be the next_cursor of a previous page".to_string(),
+    };
+    let mut parts = cursor.splitn(3, '.');
+    let (Some(secs), Some(nanos), Some(id)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let secs = secs.parse::<i64>().map_err(|_| invalid())?;
+    let nanos = nanos.parse::<u32>().map_err(|_| invalid())?;
+    Ok(SubscriptionCursor {
+        subscribed_at: chrono::DateTime::from_timestamp(secs, nanos).ok_or_else(invalid)?,
+        id: Uuid::from_str(id).map_err(|_| invalid())?,
+    })
+}
+
+async fn list_subscriptions(
+    req: api_models::ListSubscriptionsRequest,
+    repo: &dyn SubscriptionRepository,
+) -> Result<api_models::ListSubscriptionsResponse, DomainError> {
+    let limit = req.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
         return Err(DomainError::Validation {
-            field: "subscription_id".to_string(),
-            message: "Id must be a uuid".to_string(),
+            field: "limit".to_string(),
+            message: format!("limit must be between 1 and {}", MAX_PAGE_SIZE),
         });
     }
-    let res = repo.remove_subscription(id.unwrap());
-    match res {
+    let newsletter_id = req
+        .newsletter_id
+        .as_deref()
+        .map(Uuid::from_str)
+        .transpose()
+        .map_err(|_| DomainError::Validation {
+            field: "newsletter_id".to_string(),
+            message: "Id must be a uuid".to_string(),
+        })?;
+    let after = req.cursor.as_deref().map(decode_cursor).transpose()?;
+    let filter = SubscriptionFilter {
+        email: req.email.map(|email| email.trim().to_string()),
+        newsletter_id,
+        status: req.status,
+        subscribed_from: req.subscribed_from,
+        subscribed_until: req.subscribed_until,
+    };
+
+    // one extra row tells whether another page follows
+    let mut subscriptions = repo
+        .list_subscriptions(&filter, after, limit as usize + 1)
+        .await?;
+    let next_cursor = if subscriptions.len() > limit as usize {
+        subscriptions.truncate(limit as usize);
+        subscriptions.last().map(cursor_after).transpose()?
+    } else {
+        None
+    };
+    let total = repo.count_subscriptions(&filter).await?;
+    Ok(api_models::ListSubscriptionsResponse {
+        subscriptions,
+        total,
+        next_cursor,
+    })
+}
+
+async fn add_pending_subscription(
+    req: &api_models::CreateSubscriptionRequest,
+    cfg: &SubscriptionConfiguration,
+    repo: &dyn SubscriptionRepository,
+    newsletter_repo: &dyn NewsletterRepository,
+    job_repo: &dyn JobRepository,
+    limiter: &RateLimiter,
+    max_attempts: i32,
+) -> Result<(api_models::Subscription, api_models::Newsletter, bool), DomainError> {
+    let email = SubscriberEmail::parse(&req.email)?;
+    // counted per address so rotating client ips cannot flood one inbox
+    limiter.check_email(email.as_ref())?;
+    let name = SubscriberName::parse(&req.name)?;
+    let newsletter_id =
+        Uuid::from_str(req.newsletter_id.as_str()).map_err(|_| DomainError::Validation {
+            field: "newsletter_id".to_string(),
+            message: "Id must be a uuid".to_string(),
+        })?;
+    let newsletter = newsletter_repo.get_newsletter(newsletter_id).await?;
+
+    let now = SystemTime::now();
+    if let Err(err) = repo.remove_expired_confirmations(now).await {
+        tracing::warn!("failed to remove expired confirmations: {}", err);
+    }
+
+    // subscribing again with the same email and name is idempotent and resends the
+    // confirmation while still pending; the same email under another name is a conflict,
+    // unless the subscription predates subscriber names
+    let (sub, created) = match repo
+        .add_subscription(
+            newsletter_id,
+            name.as_ref().to_string(),
+            email.as_ref().to_string(),
+            now,
+        )
+        .await
+    {
+        Ok(sub) => (sub, true),
+        Err(DomainError::Conflict(msg)) => {
+            let existing = repo
+                .find_subscription(newsletter_id, email.as_ref().to_string())
+                .await?
+                .ok_or(DomainError::Conflict(msg))?;
+            if existing
+                .subscriber_name
+                .as_deref()
+                .is_some_and(|existing| existing != name.as_ref())
+            {
+                return Err(DomainError::Conflict(format!(
+                    "email is already subscribed to {} under another name",
+                    newsletter.name
+                )));
+            }
+            (existing, false)
+        }
+        Err(err) => return Err(err),
+    };
+    if sub.status == api_models::SubscriptionStatus::Confirmed {
+        return Ok((sub, newsletter, created));
+    }
+
+    let id = Uuid::from_str(sub.subscription_id.as_str())
+        .map_err(|err| DomainError::Internal(format!("invalid subscription id: {}", err)))?;
+    // the email itself goes out from the job worker, which looks the token up so it never
+    // sits in the job's payload; if it never does, the pending subscription expires along
+    // with its token
+    let queued = match repo
+        .create_confirmation_token(id, now, now + cfg.confirmation_token_ttl)
+        .await
+    {
+        Ok(_) => {
+            let job = Job::SendConfirmation {
+                subscription_id: id,
+            };
+            job_repo.enqueue_job(&job, max_attempts, now).await
+        }
         Err(err) => Err(err),
-        Ok(res) => Ok(api_models::RemoveSubscriptionResponse { subscription: res }),
+    };
+    match queued {
+        Ok(_) => Ok((sub, newsletter, created)),
+        Err(err) => {
+            // the subscriber would never receive a token to confirm with
+            if created {
+                let _ = repo.remove_subscription(id).await;
+            }
+            Err(err)
+        }
+    }
+}
+
+async fn confirm_subscription(
+    req: api_models::ConfirmSubscriptionRequest,
+    repo: &dyn SubscriptionRepository,
+) -> Result<api_models::ConfirmSubscriptionResponse, DomainError> {
+    if req.token.is_empty() {
+        return Err(DomainError::Validation {
+            field: "token".to_string(),
+            message: "token must not be empty".to_string(),
+        });
     }
+    let subscription = repo
+        .confirm_subscription(req.token, SystemTime::now())
+        .await?;
+    Ok(api_models::ConfirmSubscriptionResponse { subscription })
 }
 
+/// resolves a signed unsubscribe token to the subscription it points at, without removing
+/// it; no other auth needed
+async fn unsubscribe_preview(
+    req: api_models::UnsubscribeRequest,
+    cfg: &SubscriptionConfiguration,
+    repo: &dyn SubscriptionRepository,
+) -> Result<api_models::UnsubscribePreviewResponse, DomainError> {
+    let id = unsubscribe_token::verify(&cfg.unsubscribe_secret, &req.token)?;
+    let subscription = repo.get_subscription(id).await?;
+    Ok(api_models::UnsubscribePreviewResponse { subscription })
+}
+
+/// removes the subscription named by a signed unsubscribe token, no other auth needed
+async fn unsubscribe(
+    req: api_models::UnsubscribeRequest,
+    cfg: &SubscriptionConfiguration,
+    repo: &dyn SubscriptionRepository,
+) -> Result<api_models::RemoveSubscriptionResponse, DomainError> {
+    let id = unsubscribe_token::verify(&cfg.unsubscribe_secret, &req.token)?;
+    let subscription = repo.remove_subscription(id).await?;
+    Ok(api_models::RemoveSubscriptionResponse { subscription })
+}
+
+#[utoipa::path(
+    post,
+    path = "/subscribe",
+    tag = "subscriptions",
+    request_body = api_models::CreateSubscriptionRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "replays the first response to retries")),
+    responses(
+        (status = 201, description = "subscription created, pending confirmation", body = api_models::SubscriptionResponse),
+        (status = 200, description = "already subscribed; a pending confirmation is resent", body = api_models::SubscriptionResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 404, description = "newsletter not found", body = ErrorResponse),
+        (status = 409, description = "the email is subscribed under another name", body = ErrorResponse),
+        (status = 429, description = "rate limited", body = ErrorResponse),
+    )
+)]
 pub(crate) async fn create_subscription_handler(
     Extension(app): axum::Extension<Arc<super::app::Application>>,
     arg: Json<api_models::CreateSubscriptionRequest>,
-) -> (StatusCode, Json<api_models::SubscriptionResponse>) {
+) -> axum::response::Response {
     let repo = app.repo.clone();
-    let repo = repo.lock().unwrap();
-    let resp = repo.add_subscription(
-        arg.name.as_str().to_string(),
-        arg.email.as_str().to_string(),
-        SystemTime::now(),
-    );
+    let newsletter_repo = app.newsletter_repo.clone();
+    let job_repo = app.job_repo.clone();
+    let resp = add_pending_subscription(
+        &arg,
+        &app.subscription_cfg,
+        repo.as_ref(),
+        newsletter_repo.as_ref(),
+        job_repo.as_ref(),
+        &app.rate_limiter,
+        app.worker_cfg.max_attempts,
+    )
+    .await;
     match resp {
-        Ok(_) => (
-            StatusCode::CREATED,
-            Json(api_models::SubscriptionResponse {
-                message: format!(
-                    "Subscription created for user: {} with email: {}",
-                    arg.name, arg.email
+        Ok((sub, newsletter, created)) => {
+            let (status, message) = match (created, &sub.status) {
+                (true, _) => (
+                    StatusCode::CREATED,
+                    format!(
+                        "Subscription to {} created for user: {} with email: {}, pending confirmation",
+                        newsletter.name, arg.name.trim(), arg.email.trim()
+                    ),
                 ),
-            }),
-        ),
-        Err(_) => (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(api_models::SubscriptionResponse {
-                message: "Failed to add subscription".to_string(),
-            }),
-        ),
+                (false, api_models::SubscriptionStatus::PendingConfirmation) => (
+                    StatusCode::OK,
+                    format!(
+                        "Subscription to {} already exists for user: {} with email: {}, confirmation resent",
+                        newsletter.name, arg.name.trim(), arg.email.trim()
+                    ),
+                ),
+                (false, api_models::SubscriptionStatus::Confirmed) => (
+                    StatusCode::OK,
+                    format!(
+                        "Subscription to {} already confirmed for user: {} with email: {}",
+                        newsletter.name, arg.name.trim(), arg.email.trim()
+                    ),
+                ),
+            };
+            if created {
+                app.metrics.subscription_created();
+            }
+            tracing::info!("{} (subscription_id={})", message, sub.subscription_id);
+            (status, Json(api_models::SubscriptionResponse { message })).into_response()
+        }
+        Err(
+            err @ (DomainError::NotFound(_)
+            | DomainError::Validation { .. }
+            | DomainError::Conflict(_)
+            | DomainError::RateLimited { .. }),
+        ) => domain_errors::error_to_response(err).into_response(),
+        Err(err) => {
+            tracing::warn!("failed to add subscription: {}", err);
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Extension(ErrorVariant(err.variant())),
+                Json(api_models::SubscriptionResponse {
+                    message: "Failed to add subscription".to_string(),
+                }),
+            )
+                .into_response()
+        }
     }
 }
 
+/// looks subscriptions up by `?email=`; callers of the deprecated form still send the
+/// request as a JSON body. Lookups without an API key, which were allowed before keys, are
+/// still answered but flagged as deprecated
+#[utoipa::path(
+    get,
+    path = "/subscriptions",
+    tag = "subscriptions",
+    params(api_models::GetSubscriptionRequest),
+    security((), ("api_key" = [])),
+    responses(
+        (status = 200, description = "the subscriptions of the email; flagged with `Deprecation` when asked without an API key", body = api_models::GetSubscriptionsResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+    )
+)]
 pub(crate) async fn get_subscription_handler(
     Extension(app): axum::Extension<Arc<super::app::Application>>,
-    arg: Json<api_models::GetSubscriptionRequest>,
+    auth: MaybeAuthorized<ReadOnly>,
+    uri: Uri,
+    body: Bytes,
+) -> axum::response::Response {
+    let mut response = get_subscriptions_response(app, uri, body).await;
+    if auth.0.is_none() {
+        tracing::warn!("GET /subscriptions without an API key is deprecated");
+        response
+            .headers_mut()
+            .insert("deprecation", HeaderValue::from_static("true"));
+    }
+    response
+}
+
+async fn get_subscriptions_response(
+    app: Arc<super::app::Application>,
+    uri: Uri,
+    body: Bytes,
 ) -> axum::response::Response {
     let repo = app.repo.clone();
-    let mut repo = repo.lock().unwrap();
-    let res = get_subscriptions(arg.0, &mut *repo);
-    match res {
-        Ok(t) => {
-            let json_body = serde_json::to_string(&t).unwrap_or_else(|_| "{}".to_string());
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(CONTENT_TYPE, "application/json")
-                .body(axum::body::Body::from(json_body))
-                .unwrap()
-        }
-        Err(e) => domain_errors::error_to_response(e).into_response(),
+    if uri.query().is_none() && !body.is_empty() {
+        let res = match serde_json::from_slice::<api_models::GetSubscriptionRequest>(&body) {
+            Ok(req) => get_subscriptions(req, repo.as_ref()).await,
+            Err(err) => Err(DomainError::Validation {
+                field: "body".to_string(),
+                message: err.to_string(),
+            }),
+        };
+        return deprecated(
+            to_response(StatusCode::OK, res),
+            &format!("{}/subscriptions", API_PREFIX),
+        );
+    }
+    let res = match Query::<api_models::GetSubscriptionRequest>::try_from_uri(&uri) {
+        Ok(Query(req)) => get_subscriptions(req, repo.as_ref()).await,
+        Err(rejection) => Err(DomainError::Validation {
+            field: "query".to_string(),
+            message: rejection.body_text(),
+        }),
+    };
+    to_response(StatusCode::OK, res)
+}
+
+#[utoipa::path(
+    get,
+    path = "/subscriptions/{subscription_id}",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path, description = "uuid of the subscription")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the subscription", body = api_models::Subscription),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_subscription_by_id_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<ReadOnly>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let repo = app.repo.clone();
+    let res = match parse_subscription_id(&id) {
+        Ok(id) => repo.get_subscription(id).await,
+        Err(err) => Err(err),
+    };
+    to_response(StatusCode::OK, res)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/subscriptions/{subscription_id}",
+    tag = "subscriptions",
+    params(
+        ("subscription_id" = String, Path, description = "uuid of the subscription"),
+        ("Idempotency-Key" = Option<String>, Header, description = "replays the first response to retries"),
+    ),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "the removed subscription", body = api_models::RemoveSubscriptionResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn delete_subscription_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    auth: Authorized<Admin>,
+    Path(id): Path<String>,
+) -> axum::response::Response {
+    let repo = app.repo.clone();
+    let res = remove_subscription(&id, &auth.api_key, repo.as_ref()).await;
+    if res.is_ok() {
+        app.metrics.subscription_removed();
     }
+    to_response(StatusCode::OK, res)
 }
 
+/// the deprecated form of `DELETE /subscriptions/{id}`, naming the subscription in the body
+#[utoipa::path(
+    delete,
+    path = "/subscribe",
+    tag = "subscriptions",
+    request_body = api_models::RemoveSubscriptionRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "replays the first response to retries")),
+    security(("api_key" = [])),
+    responses(
+        (status = 204, description = "subscription removed"),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+    )
+)]
 pub(crate) async fn remove_subscription_handler(
     Extension(app): axum::Extension<Arc<super::app::Application>>,
+    auth: Authorized<Admin>,
     arg: Json<api_models::RemoveSubscriptionRequest>,
 ) -> axum::response::Response {
     let repo = app.repo.clone();
-    let mut repo = repo.lock().unwrap();
-    let res = remove_subscription(arg.0, &mut *repo);
-    match res {
-        Ok(t) => {
-            let json_body = serde_json::to_string(&t).unwrap_or_else(|_| "{}".to_string());
-            Response::builder()
-                .status(StatusCode::NO_CONTENT)
-                .header(CONTENT_TYPE, "application/json")
-                .body(axum::body::Body::from(json_body))
-                .unwrap()
-        }
-        Err(e) => domain_errors::error_to_response(e).into_response(),
+    let res = remove_subscription(&arg.subscription_id, &auth.api_key, repo.as_ref()).await;
+    if res.is_ok() {
+        app.metrics.subscription_removed();
     }
+    deprecated(
+        to_response(StatusCode::NO_CONTENT, res),
+        &format!("{}/subscriptions/{}", API_PREFIX, arg.subscription_id),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/subscriptions/confirm",
+    tag = "subscriptions",
+    params(api_models::ConfirmSubscriptionRequest),
+    responses(
+        (status = 200, description = "subscription confirmed", body = api_models::ConfirmSubscriptionResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 404, description = "unknown or expired token", body = ErrorResponse),
+        (status = 429, description = "rate limited", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn confirm_subscription_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    Query(arg): Query<api_models::ConfirmSubscriptionRequest>,
+) -> axum::response::Response {
+    let repo = app.repo.clone();
+    let res = confirm_subscription(arg, repo.as_ref()).await;
+    to_response(StatusCode::OK, res)
+}
+
+/// the link a subscriber clicks only shows what would be removed, since link scanners in
+/// mail clients follow it too
+#[utoipa::path(
+    get,
+    path = "/unsubscribe",
+    tag = "subscriptions",
+    params(api_models::UnsubscribeRequest),
+    responses(
+        (status = 200, description = "the subscription a POST to the same link removes", body = api_models::UnsubscribePreviewResponse),
+        (status = 400, description = "invalid token", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+        (status = 429, description = "rate limited", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn unsubscribe_preview_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    Query(arg): Query<api_models::UnsubscribeRequest>,
+) -> axum::response::Response {
+    let repo = app.repo.clone();
+    let res = unsubscribe_preview(arg, &app.subscription_cfg, repo.as_ref()).await;
+    to_response(StatusCode::OK, res)
+}
+
+/// removes the subscription, for the RFC 8058 one-click POST from mail clients and the
+/// subscriber confirming the link
+#[utoipa::path(
+    post,
+    path = "/unsubscribe",
+    tag = "subscriptions",
+    params(api_models::UnsubscribeRequest),
+    responses(
+        (status = 200, description = "subscription removed", body = api_models::RemoveSubscriptionResponse),
+        (status = 400, description = "invalid token", body = ErrorResponse),
+        (status = 404, description = "subscription not found", body = ErrorResponse),
+        (status = 429, description = "rate limited", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn unsubscribe_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    Query(arg): Query<api_models::UnsubscribeRequest>,
+) -> axum::response::Response {
+    let repo = app.repo.clone();
+    let res = unsubscribe(arg, &app.subscription_cfg, repo.as_ref()).await;
+    if res.is_ok() {
+        app.metrics.subscription_removed();
+    }
+    to_response(StatusCode::OK, res)
+}
+
+/// lists subscriptions for an operator, filtered and paged through the query string
+#[utoipa::path(
+    get,
+    path = "/admin/subscriptions",
+    tag = "subscriptions",
+    params(api_models::ListSubscriptionsRequest),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "a page of subscriptions", body = api_models::ListSubscriptionsResponse),
+        (status = 400, description = "invalid request", body = ErrorResponse),
+        (status = 401, description = "missing or unknown API key", body = ErrorResponse),
+        (status = 403, description = "the API key's role does not allow this", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn list_subscriptions_handler(
+    Extension(app): axum::Extension<Arc<super::app::Application>>,
+    _: Authorized<ReadOnly>,
+    arg: Result<Query<api_models::ListSubscriptionsRequest>, QueryRejection>,
+) -> axum::response::Response {
+    let res = match arg {
+        Ok(Query(arg)) => list_subscriptions(arg, app.repo.as_ref()).await,
+        Err(rejection) => Err(DomainError::Validation {
+            field: "query".to_string(),
+            message: rejection.body_text(),
+        }),
+    };
+    to_response(StatusCode::OK, res)
 }
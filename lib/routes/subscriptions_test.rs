@@ -0,0 +1,241 @@
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use std::time::{self, Duration};
+
+    use crate::adapter::configuration::{SubscriptionConfiguration, WorkerConfiguration};
+    use crate::adapter::repository::{
+        ApiKeyRepository, JobRepository, NewsletterRepository, SubscriptionRepository,
+    };
+    use crate::api::{self, Application, InMemoryRepository};
+    use crate::domain::api_key;
+    use crate::model::models as api_models;
+    use axum::body::{self, Body};
+    use axum::http::{header, Method, Request, StatusCode};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    const ADMIN_KEY: &str = "nl_admin";
+    const READ_ONLY_KEY: &str = "nl_read_only";
+
+    /// an app on `repo` that knows `ADMIN_KEY` and `READ_ONLY_KEY`
+    async fn new_app(repo: &InMemoryRepository) -> axum::Router {
+        let now = time::SystemTime::now();
+        for (key, role) in [
+            (ADMIN_KEY, api_models::ApiKeyRole::Admin),
+            (READ_ONLY_KEY, api_models::ApiKeyRole::ReadOnly),
+        ] {
+            repo.add_api_key(key.to_string(), role, api_key::hash(key), now)
+                .await
+                .unwrap();
+        }
+        api::app_with(Application::in_memory(
+            repo,
+            SubscriptionConfiguration {
+                confirmation_token_ttl: Duration::from_secs(60),
+                base_url: "http://localhost:3000".to_string(),
+                unsubscribe_secret: "unsubscribe-secret".to_string(),
+            },
+            WorkerConfiguration {
+                poll_interval: Duration::from_millis(10),
+                max_attempts: 5,
+                retry_delay: Duration::from_secs(1),
+                max_retry_delay: Duration::from_secs(60),
+                lease: Duration::from_secs(30),
+            },
+        ))
+    }
+
+    async fn add_newsletter(repo: &InMemoryRepository) -> Uuid {
+        let newsletter = repo
+            .add_newsletter(
+                "Weekly".to_string(),
+                "".to_string(),
+                time::SystemTime::now(),
+            )
+            .await
+            .unwrap();
+        Uuid::from_str(newsletter.newsletter_id.as_str()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribe_without_database() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let newsletter_id = add_newsletter(&repo).await;
+        let app = new_app(&repo).await;
+        let subscribe = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(format!(
+                r#"{{"email": "ursula@example.com", "name": "Ursula", "newsletter_id": "{}"}}"#,
+                newsletter_id
+            )))
+            .unwrap();
+
+        // act
+        let response = app.oneshot(subscribe).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CREATED, response.status());
+        let subs = repo
+            .get_subscriptions("ursula@example.com".to_string(), true)
+            .await;
+        assert_eq!(1, subs.len());
+        let queued = repo.get_jobs(api_models::JobStatus::Queued).await.unwrap();
+        assert_eq!(1, queued.len());
+        assert_eq!("send_confirmation", queued[0].kind);
+    }
+
+    #[tokio::test]
+    async fn confirm_and_remove_subscription_without_database() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let newsletter_id = add_newsletter(&repo).await;
+        let now = time::SystemTime::now();
+        let sub = repo
+            .add_subscription(
+                newsletter_id,
+                "Ursula".to_string(),
+                "ursula@example.com".to_string(),
+                now,
+            )
+            .await
+            .unwrap();
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let token = repo
+            .create_confirmation_token(id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        let app = new_app(&repo).await;
+
+        // act
+        let confirm = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/subscriptions/confirm?token={}", token))
+            .body(Body::empty())
+            .unwrap();
+        let confirmed = app.clone().oneshot(confirm).await.unwrap();
+        let list = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/subscriptions")
+            .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_KEY))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"email": "ursula@example.com"}"#))
+            .unwrap();
+        let listed = app.clone().oneshot(list).await.unwrap();
+        let remove = Request::builder()
+            .method(Method::DELETE)
+            .uri("/v1/subscribe")
+            .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_KEY))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(format!(r#"{{"subscription_id": "{}"}}"#, id)))
+            .unwrap();
+        let removed = app.clone().oneshot(remove).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, confirmed.status());
+        assert_eq!(StatusCode::OK, listed.status());
+        let bytes = body::to_bytes(listed.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listed: api_models::GetSubscriptionsResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(1, listed.resp.len());
+        assert_eq!(
+            api_models::SubscriptionStatus::Confirmed,
+            listed.resp[0].status
+        );
+        assert_eq!(StatusCode::NO_CONTENT, removed.status());
+        assert!(repo
+            .get_subscriptions("ursula@example.com".to_string(), true)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn admin_routes_require_a_key_with_the_role() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let app = new_app(&repo).await;
+        let remove = |key: Option<&str>| {
+            let builder = Request::builder()
+                .method(Method::DELETE)
+                .uri(format!("/v1/subscriptions/{}", Uuid::new_v4()));
+            match key {
+                Some(key) => builder.header(header::AUTHORIZATION, format!("Bearer {}", key)),
+                None => builder,
+            }
+            .body(Body::empty())
+            .unwrap()
+        };
+
+        // act
+        let missing = app.clone().oneshot(remove(None)).await.unwrap();
+        let unknown = app
+            .clone()
+            .oneshot(remove(Some("nl_unknown")))
+            .await
+            .unwrap();
+        let read_only = app
+            .clone()
+            .oneshot(remove(Some(READ_ONLY_KEY)))
+            .await
+            .unwrap();
+        let admin = app.clone().oneshot(remove(Some(ADMIN_KEY))).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::UNAUTHORIZED, missing.status());
+        assert_eq!("Bearer", missing.headers()[header::WWW_AUTHENTICATE]);
+        assert_eq!(StatusCode::UNAUTHORIZED, unknown.status());
+        assert_eq!(StatusCode::FORBIDDEN, read_only.status());
+        assert_eq!(StatusCode::NOT_FOUND, admin.status());
+    }
+
+    #[tokio::test]
+    async fn lookup_without_a_key_is_deprecated() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let newsletter_id = add_newsletter(&repo).await;
+        repo.add_subscription(
+            newsletter_id,
+            "Ursula".to_string(),
+            "ursula@example.com".to_string(),
+            time::SystemTime::now(),
+        )
+        .await
+        .unwrap();
+        let app = new_app(&repo).await;
+        let lookup = |key: Option<&str>| {
+            let builder = Request::builder()
+                .method(Method::GET)
+                .uri("/v1/subscriptions?email=ursula@example.com&include_pending=true");
+            match key {
+                Some(key) => builder.header(header::AUTHORIZATION, format!("Bearer {}", key)),
+                None => builder,
+            }
+            .body(Body::empty())
+            .unwrap()
+        };
+
+        // act
+        let anonymous = app.clone().oneshot(lookup(None)).await.unwrap();
+        let unknown = app
+            .clone()
+            .oneshot(lookup(Some("nl_unknown")))
+            .await
+            .unwrap();
+        let read_only = app
+            .clone()
+            .oneshot(lookup(Some(READ_ONLY_KEY)))
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, anonymous.status());
+        assert_eq!("true", anonymous.headers()["deprecation"]);
+        assert_eq!(StatusCode::UNAUTHORIZED, unknown.status());
+        assert_eq!(StatusCode::OK, read_only.status());
+        assert!(!read_only.headers().contains_key("deprecation"));
+    }
+}
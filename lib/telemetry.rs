@@ -0,0 +1,144 @@
+use axum::extract::Request;
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::{Span, Subscriber};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::adapter::configuration::TelemetryConfiguration;
+
+/// the layer exporting spans to `cfg.otlp_endpoint`, if one is configured
+#[cfg(feature = "otel")]
+pub fn layer<S>(cfg: &TelemetryConfiguration) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    let endpoint = cfg.otlp_endpoint.as_ref()?;
+    let tracer = otel::install(endpoint, &cfg.service_name)
+        .map_err(|err| eprintln!("failed to set up OTLP export to {}: {}", endpoint, err))
+        .ok()?;
+    Some(
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(tracing_subscriber::filter::LevelFilter::INFO)
+            .boxed(),
+    )
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn layer<S>(cfg: &TelemetryConfiguration) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    if cfg.otlp_endpoint.is_some() {
+        eprintln!(
+            "telemetry.otlp_endpoint is ignored, the service was built without the otel feature"
+        );
+    }
+    None
+}
+
+/// makes `span` a child of the W3C `traceparent` the caller sent, if any
+pub fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+    #[cfg(feature = "otel")]
+    otel::set_remote_parent(span, headers);
+    #[cfg(not(feature = "otel"))]
+    let _ = (span, headers);
+}
+
+/// returns the `traceparent` of the request span so callers can join their trace to ours
+pub async fn propagate(request: Request, next: Next) -> Response {
+    #[allow(unused_mut)]
+    let mut response = next.run(request).await;
+    #[cfg(feature = "otel")]
+    otel::inject(&Span::current(), response.headers_mut());
+    response
+}
+
+/// exports whatever spans are still buffered; blocks, so call it off the async executor
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+
+    use axum::http::{HeaderMap, HeaderName, HeaderValue};
+    use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+    use opentelemetry_sdk::Resource;
+    use tracing::Span;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+    pub(super) fn install(endpoint: &str, service_name: &str) -> Result<SdkTracer, String> {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()
+            .map_err(|err| err.to_string())?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(service_name.to_string())
+                    .build(),
+            )
+            .build();
+        let tracer = provider.tracer("newsletter_service");
+        PROVIDER
+            .set(provider)
+            .map_err(|_| "telemetry is already installed".to_string())?;
+        Ok(tracer)
+    }
+
+    struct Headers<'a>(&'a HeaderMap);
+
+    impl Extractor for Headers<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(HeaderName::as_str).collect()
+        }
+    }
+
+    struct HeadersMut<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeadersMut<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    pub(super) fn set_remote_parent(span: &Span, headers: &HeaderMap) {
+        let parent = TraceContextPropagator::new().extract(&Headers(headers));
+        // fails only when no exporting layer is installed, leaving nothing to link
+        let _ = span.set_parent(parent);
+    }
+
+    pub(super) fn inject(span: &Span, headers: &mut HeaderMap) {
+        TraceContextPropagator::new().inject_context(&span.context(), &mut HeadersMut(headers));
+    }
+
+    pub(super) fn shutdown() {
+        if let Some(provider) = PROVIDER.get() {
+            if let Err(err) = provider.shutdown() {
+                tracing::warn!("failed to flush spans: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::adapter::configuration::{SubscriptionConfiguration, WorkerConfiguration};
+use crate::adapter::email_client::{EmailClient, EmailMessage};
+use crate::adapter::repository::{
+    IssueRepository, JobRepository, NewsletterRepository, SubscriptionRepository,
+};
+use crate::api::API_PREFIX;
+use crate::domain::errors::DomainError;
+use crate::domain::jobs::Job;
+use crate::domain::unsubscribe_token;
+use crate::model::models as api_models;
+use crate::shutdown::Shutdown;
+use uuid::Uuid;
+
+pub struct JobWorker {
+    jobs: Arc<dyn JobRepository>,
+    subscriptions: Arc<dyn SubscriptionRepository>,
+    newsletters: Arc<dyn NewsletterRepository>,
+    issues: Arc<dyn IssueRepository>,
+    email_client: Arc<dyn EmailClient + Send + Sync>,
+    subscription_cfg: Arc<SubscriptionConfiguration>,
+    cfg: WorkerConfiguration,
+}
+
+/// delay before the next attempt, doubling per failed attempt like `connection_pool`
+pub fn retry_delay(cfg: &WorkerConfiguration, attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 31) as u32;
+    cfg.retry_delay
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(cfg.max_retry_delay)
+}
+
+impl JobWorker {
+    pub fn new(
+        jobs: Arc<dyn JobRepository>,
+        subscriptions: Arc<dyn SubscriptionRepository>,
+        newsletters: Arc<dyn NewsletterRepository>,
+        issues: Arc<dyn IssueRepository>,
+        email_client: Arc<dyn EmailClient + Send + Sync>,
+        subscription_cfg: SubscriptionConfiguration,
+        cfg: WorkerConfiguration,
+    ) -> Self {
+        Self {
+            jobs,
+            subscriptions,
+            newsletters,
+            issues,
+            email_client,
+            subscription_cfg: Arc::new(subscription_cfg),
+            cfg,
+        }
+    }
+
+    /// runs at most one due job, returning whether there was one
+    pub async fn run_once(&self) -> Result<bool, DomainError> {
+        let now = SystemTime::now();
+        let Some(claimed) = self.jobs.claim_job(now, self.cfg.lease).await? else {
+            return Ok(false);
+        };
+
+        match self.execute(&claimed.job).await {
+            Ok(_) => {
+                self.jobs
+                    .complete_job(claimed.id, SystemTime::now())
+                    .await?;
+                tracing::info!("job {} ({}) succeeded", claimed.id, claimed.job.kind());
+            }
+            Err(err) => {
+                let now = SystemTime::now();
+                let retry_at = now + retry_delay(&self.cfg, claimed.attempts);
+                let status = self
+                    .jobs
+                    .fail_job(claimed.id, err.to_string(), retry_at, now)
+                    .await?;
+                if status == api_models::JobStatus::Dead {
+                    tracing::error!(
+                        "job {} ({}) failed permanently after {} attempts: {}",
+                        claimed.id,
+                        claimed.job.kind(),
+                        claimed.attempts,
+                        err
+                    );
+                } else {
+                    tracing::warn!(
+                        "job {} ({}) failed on attempt {}, retrying: {}",
+                        claimed.id,
+                        claimed.job.kind(),
+                        claimed.attempts,
+                        err
+                    );
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    async fn execute(&self, job: &Job) -> Result<(), DomainError> {
+        match job {
+            Job::SendConfirmation { subscription_id } => {
+                self.send_confirmation(*subscription_id).await
+            }
+            Job::PublishIssue {
+                newsletter_id,
+                issue_id,
+            } => self.publish_issue(*newsletter_id, *issue_id).await,
+        }
+    }
+
+    /// emails the confirmation link of a subscription that is still pending; one that was
+    /// confirmed, removed or let expire in the meantime has nothing left to confirm
+    async fn send_confirmation(&self, subscription_id: Uuid) -> Result<(), DomainError> {
+        let sub = match self.subscriptions.get_subscription(subscription_id).await {
+            Ok(sub) => sub,
+            Err(DomainError::NotFound(_)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        if sub.status == api_models::SubscriptionStatus::Confirmed {
+            return Ok(());
+        }
+        let Some(token) = self
+            .subscriptions
+            .confirmation_token(subscription_id, SystemTime::now())
+            .await?
+        else {
+            return Ok(());
+        };
+        let newsletter_id = Uuid::from_str(&sub.newsletter_id)
+            .map_err(|err| DomainError::Internal(format!("invalid newsletter id: {}", err)))?;
+        let newsletter = self.newsletters.get_newsletter(newsletter_id).await?;
+        let message = confirmation_email(
+            sub.email.unwrap_or_default(),
+            &newsletter,
+            &self.subscription_cfg,
+            subscription_id,
+            &token,
+        );
+
+        // email clients block on io, keep them off the async executor
+        let email_client = self.email_client.clone();
+        tokio::task::spawn_blocking(move || email_client.send_email(&message))
+            .await
+            .map_err(|err| DomainError::Internal(format!("email task failed (Error = {})", err)))?
+    }
+
+    /// delivers the issue to every confirmed subscriber; a retry only resends the
+    /// deliveries that did not go out the first time
+    async fn publish_issue(&self, newsletter_id: Uuid, issue_id: Uuid) -> Result<(), DomainError> {
+        let issue = self.issues.get_issue(newsletter_id, issue_id).await?;
+        self.issues
+            .enqueue_deliveries(issue_id, SystemTime::now())
+            .await?;
+
+        let email_client = self.email_client.clone();
+        let subscription_cfg = self.subscription_cfg.clone();
+        self.issues
+            .deliver_pending(
+                issue_id,
+                Box::new(move |recipient| {
+                    email_client.send_email(&EmailMessage {
+                        to: recipient.email.clone(),
+                        subject: issue.title.clone(),
+                        html_body: issue.html_body.clone(),
+                        text_body: issue.text_body.clone(),
+                        unsubscribe_url: Some(unsubscribe_token::unsubscribe_url(
+                            &subscription_cfg,
+                            recipient.subscription_id,
+                        )),
+                    })
+                }),
+            )
+            .await?;
+
+        let summary = self.issues.delivery_summary(issue_id).await?;
+        if summary.failed > 0 {
+            return Err(DomainError::Internal(format!(
+                "{} deliveries of issue {} failed",
+                summary.failed, issue_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// escapes the characters that are markup in html text and quoted attribute values
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn confirmation_email(
+    to: String,
+    newsletter: &api_models::Newsletter,
+    cfg: &SubscriptionConfiguration,
+    subscription_id: Uuid,
+    token: &str,
+) -> EmailMessage {
+    let link = format!(
+        "{}{}/subscriptions/confirm?token={}",
+        cfg.base_url.trim_end_matches('/'),
+        API_PREFIX,
+        token
+    );
+    EmailMessage {
+        to,
+        subject: format!("Confirm your subscription to {}", newsletter.name),
+        html_body: format!(
+            "<p>Please confirm your subscription to {}.</p><p><a href=\"{}\">Confirm subscription</a></p>",
+            escape_html(&newsletter.name),
+            escape_html(&link)
+        ),
+        text_body: format!(
+            "Please confirm your subscription to {}.\nVisit {} to confirm.",
+            newsletter.name, link
+        ),
+        unsubscribe_url: Some(unsubscribe_token::unsubscribe_url(cfg, subscription_id)),
+    }
+}
+
+/// polls the queue until `shutdown` is triggered, sleeping for the poll interval whenever
+/// it is empty; a job that is already running is always finished first
+pub async fn run(worker: JobWorker, shutdown: Shutdown) {
+    let poll_interval = worker.cfg.poll_interval;
+    while !shutdown.is_triggered() {
+        match worker.run_once().await {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(err) => tracing::error!("job worker error: {}", err),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(poll_interval) => {},
+            _ = shutdown.triggered() => {},
+        }
+    }
+    tracing::info!("job worker stopped");
+}
@@ -0,0 +1,300 @@
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::time::{self, Duration};
+
+    use crate::adapter::configuration::{SubscriptionConfiguration, WorkerConfiguration};
+    use crate::adapter::email_client::InMemoryEmailClient;
+    use crate::adapter::memory_repository::InMemoryRepository;
+    use crate::adapter::repository::{
+        IssueRepository, JobRepository, NewsletterRepository, SubscriptionRepository,
+    };
+    use crate::domain::jobs::Job;
+    use crate::domain::unsubscribe_token;
+    use crate::model::models as api_models;
+    use crate::shutdown::Shutdown;
+    use crate::worker::job_worker::{retry_delay, run, JobWorker};
+    use uuid::Uuid;
+
+    fn get_worker_configuration() -> WorkerConfiguration {
+        WorkerConfiguration {
+            poll_interval: Duration::from_millis(10),
+            max_attempts: 5,
+            retry_delay: Duration::from_secs(2),
+            max_retry_delay: Duration::from_secs(60),
+            lease: Duration::from_secs(30),
+        }
+    }
+
+    fn new_worker(repo: &InMemoryRepository, email_client: &InMemoryEmailClient) -> JobWorker {
+        JobWorker::new(
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(email_client.clone()),
+            SubscriptionConfiguration {
+                confirmation_token_ttl: Duration::from_secs(60),
+                base_url: "http://localhost:3000".to_string(),
+                unsubscribe_secret: "unsubscribe-secret".to_string(),
+            },
+            get_worker_configuration(),
+        )
+    }
+
+    async fn enqueue(repo: &InMemoryRepository, job: &Job) {
+        repo.enqueue_job(job, 5, time::SystemTime::now())
+            .await
+            .unwrap();
+    }
+
+    async fn jobs(
+        repo: &InMemoryRepository,
+        status: api_models::JobStatus,
+    ) -> Vec<api_models::Job> {
+        repo.get_jobs(status).await.unwrap()
+    }
+
+    async fn add_newsletter(repo: &InMemoryRepository, name: String) -> Uuid {
+        let newsletter = repo
+            .add_newsletter(name, "".to_string(), time::SystemTime::now())
+            .await
+            .unwrap();
+        Uuid::from_str(newsletter.newsletter_id.as_str()).unwrap()
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        // arrange
+        let cfg = get_worker_configuration();
+
+        // act
+        let delays: Vec<u64> = (1..=7).map(|n| retry_delay(&cfg, n).as_secs()).collect();
+
+        // assert
+        assert_eq!(vec![2, 4, 8, 16, 32, 60, 60], delays);
+    }
+
+    #[tokio::test]
+    async fn run_once_empty_queue() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(!result.unwrap());
+        assert!(email_client.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn run_stops_once_shutdown_is_triggered() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let email_client = InMemoryEmailClient::new();
+        let shutdown = Shutdown::new();
+        let handle = tokio::spawn(run(new_worker(&repo, &email_client), shutdown.clone()));
+
+        // act
+        shutdown.trigger();
+        let stopped = tokio::time::timeout(Duration::from_secs(5), handle).await;
+
+        // assert
+        assert!(stopped.is_ok(), "worker kept polling after shutdown");
+    }
+
+    /// a pending subscription of ursula@example.com to a new newsletter named `name`, with
+    /// its confirmation token
+    async fn pending_subscription(repo: &InMemoryRepository, name: &str) -> (Uuid, String) {
+        let now = time::SystemTime::now();
+        let newsletter_id = add_newsletter(repo, name.to_string()).await;
+        let sub = repo
+            .add_subscription(
+                newsletter_id,
+                "Ursula".to_string(),
+                "ursula@example.com".to_string(),
+                now,
+            )
+            .await
+            .unwrap();
+        let id = Uuid::from_str(sub.subscription_id.as_str()).unwrap();
+        let token = repo
+            .create_confirmation_token(id, now, now + Duration::from_secs(60))
+            .await
+            .unwrap();
+        (id, token)
+    }
+
+    #[tokio::test]
+    async fn run_once_send_confirmation() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let (subscription_id, token) = pending_subscription(&repo, "Weekly").await;
+        let job = Job::SendConfirmation { subscription_id };
+        enqueue(&repo, &job).await;
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(result.unwrap());
+        assert_eq!(1, jobs(&repo, api_models::JobStatus::Succeeded).await.len());
+        let sent = email_client.sent();
+        assert_eq!(1, sent.len());
+        assert_eq!("ursula@example.com", sent[0].to);
+        let link = format!(
+            "http://localhost:3000/v1/subscriptions/confirm?token={}",
+            token
+        );
+        assert!(sent[0].text_body.contains(&link));
+        assert!(!serde_json::to_string(&job).unwrap().contains(&token));
+    }
+
+    #[tokio::test]
+    async fn run_once_send_confirmation_escapes_html() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let name = "<script>alert(1)</script> & co";
+        let (subscription_id, _) = pending_subscription(&repo, name).await;
+        enqueue(&repo, &Job::SendConfirmation { subscription_id }).await;
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(result.unwrap());
+        let sent = email_client.sent();
+        assert_eq!(1, sent.len());
+        assert!(!sent[0].html_body.contains("<script>"));
+        assert!(sent[0]
+            .html_body
+            .contains("&lt;script&gt;alert(1)&lt;/script&gt; &amp; co"));
+        assert!(sent[0].text_body.contains(name));
+    }
+
+    #[tokio::test]
+    async fn run_once_send_confirmation_once_confirmed() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let (subscription_id, token) = pending_subscription(&repo, "Weekly").await;
+        repo.confirm_subscription(token, time::SystemTime::now())
+            .await
+            .unwrap();
+        enqueue(&repo, &Job::SendConfirmation { subscription_id }).await;
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(result.unwrap());
+        assert_eq!(1, jobs(&repo, api_models::JobStatus::Succeeded).await.len());
+        assert!(email_client.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn run_once_failure_backs_off() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let job = Job::PublishIssue {
+            newsletter_id: Uuid::new_v4(),
+            issue_id: Uuid::new_v4(),
+        };
+        enqueue(&repo, &job).await;
+        // two earlier attempts failed and are due again right away
+        for _ in 0..2 {
+            let now = time::SystemTime::now();
+            let claimed = repo
+                .claim_job(now, Duration::from_secs(30))
+                .await
+                .unwrap()
+                .unwrap();
+            repo.fail_job(claimed.id, "failed".to_string(), now, now)
+                .await
+                .unwrap();
+        }
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+        let before: chrono::DateTime<chrono::Utc> = time::SystemTime::now().into();
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(result.unwrap());
+        let queued = jobs(&repo, api_models::JobStatus::Queued).await;
+        assert_eq!(1, queued.len());
+        assert_eq!(3, queued[0].attempts);
+        // third attempt waits 2s * 2^2
+        assert!(queued[0].run_at >= before + Duration::from_secs(8));
+        assert!(queued[0].run_at < before + Duration::from_secs(16));
+    }
+
+    #[tokio::test]
+    async fn run_once_publish_issue() {
+        // arrange
+        let repo = InMemoryRepository::new();
+        let (subscription_id, token) = pending_subscription(&repo, "Weekly").await;
+        let now = time::SystemTime::now();
+        repo.confirm_subscription(token, now).await.unwrap();
+        let newsletter_id = Uuid::from_str(
+            repo.get_subscription(subscription_id)
+                .await
+                .unwrap()
+                .newsletter_id
+                .as_str(),
+        )
+        .unwrap();
+        let issue = repo
+            .add_issue(
+                newsletter_id,
+                "Issue #1".to_string(),
+                "hello".to_string(),
+                "<p>hello</p>".to_string(),
+                now,
+            )
+            .await
+            .unwrap();
+        let issue_id = Uuid::from_str(issue.issue_id.as_str()).unwrap();
+        enqueue(
+            &repo,
+            &Job::PublishIssue {
+                newsletter_id,
+                issue_id,
+            },
+        )
+        .await;
+        let email_client = InMemoryEmailClient::new();
+        let worker = new_worker(&repo, &email_client);
+
+        // act
+        let result = worker.run_once().await;
+
+        // assert
+        assert!(result.unwrap());
+        assert_eq!(1, jobs(&repo, api_models::JobStatus::Succeeded).await.len());
+        let sent = email_client.sent();
+        assert_eq!(1, sent.len());
+        assert_eq!("ursula@example.com", sent[0].to);
+        assert_eq!("Issue #1", sent[0].subject);
+        let unsubscribe_url = sent[0].unsubscribe_url.clone().unwrap();
+        let token = unsubscribe_url
+            .strip_prefix("http://localhost:3000/v1/unsubscribe?token=")
+            .unwrap();
+        assert_eq!(
+            subscription_id,
+            unsubscribe_token::verify("unsubscribe-secret", token).unwrap()
+        );
+        let summary = repo.delivery_summary(issue_id).await.unwrap();
+        assert_eq!(1, summary.sent);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod job_worker;
+pub(super) mod job_worker_test;
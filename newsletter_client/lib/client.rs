@@ -0,0 +1,96 @@
+use reqwest::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use service::model::models::{
+    ConfirmSubscriptionRequest, ConfirmSubscriptionResponse, CreateSubscriptionRequest,
+    ListSubscriptionsRequest, ListSubscriptionsResponse, RemoveSubscriptionResponse,
+    SubscriptionResponse,
+};
+
+use crate::error::Error;
+
+/// the version of the API this client speaks
+const API_PREFIX: &str = "/v1";
+
+/// calls the newsletter service at `base_url`, e.g. `http://newsletter:8081`
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// shares `http`'s connection pool, timeouts and TLS settings
+    pub fn with_http_client(http: reqwest::Client, base_url: &str) -> Self {
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: None,
+        }
+    }
+
+    /// sends `api_key` as a bearer token, as listing and removing subscriptions require
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// subscribes to a newsletter; the subscription stays pending until confirmed
+    pub async fn subscribe(
+        &self,
+        req: &CreateSubscriptionRequest,
+    ) -> Result<SubscriptionResponse, Error> {
+        self.send(self.request(Method::POST, "/subscribe").json(req))
+            .await
+    }
+
+    /// a page of subscriptions matching every filter set in `req`
+    pub async fn list(
+        &self,
+        req: &ListSubscriptionsRequest,
+    ) -> Result<ListSubscriptionsResponse, Error> {
+        self.send(self.request(Method::GET, "/admin/subscriptions").query(req))
+            .await
+    }
+
+    /// removes a subscription, returning it
+    pub async fn remove(&self, subscription_id: &str) -> Result<RemoveSubscriptionResponse, Error> {
+        let path = format!("/subscriptions/{}", subscription_id);
+        self.send(self.request(Method::DELETE, &path)).await
+    }
+
+    /// confirms a subscription with the token from its confirmation email
+    pub async fn confirm(&self, token: &str) -> Result<ConfirmSubscriptionResponse, Error> {
+        let req = ConfirmSubscriptionRequest {
+            token: token.to_string(),
+        };
+        self.send(
+            self.request(Method::GET, "/subscriptions/confirm")
+                .query(&req),
+        )
+        .await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{}{}", self.base_url, API_PREFIX, path);
+        let builder = self.http.request(method, url);
+        match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> Result<T, Error> {
+        let response = builder.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+        let body = response.bytes().await?;
+        Err(Error::from_response(status, &body))
+    }
+}
@@ -0,0 +1,115 @@
+use core::fmt;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use service::model::models::ErrorResponse;
+
+/// a failed call, by the kind of error the service reported
+#[derive(Debug)]
+pub enum Error {
+    NotFound(ErrorResponse),
+    /// `field` and `message` of the body name what was invalid
+    Validation(ErrorResponse),
+    /// the request clashes with the current state of a resource
+    Conflict(ErrorResponse),
+    /// no API key was configured, or the service does not know it
+    Unauthorized(ErrorResponse),
+    /// the API key's role does not allow the request
+    Forbidden(ErrorResponse),
+    /// e.g. an idempotency key reused for a different request
+    Unprocessable(ErrorResponse),
+    /// the caller used up its quota and may try again after `retry_after`
+    RateLimited {
+        retry_after: Option<Duration>,
+        body: ErrorResponse,
+    },
+    Internal(ErrorResponse),
+    /// a response without an error body, e.g. from a proxy in front of the service
+    Unexpected {
+        status: StatusCode,
+        body: String,
+    },
+    /// the request could not be sent, or its response could not be read
+    Transport(reqwest::Error),
+}
+
+impl Error {
+    /// maps an error response back to the error the service returned
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        let Ok(error) = serde_json::from_slice::<ErrorResponse>(body) else {
+            return Error::Unexpected {
+                status,
+                body: String::from_utf8_lossy(body).into_owned(),
+            };
+        };
+        match status {
+            StatusCode::NOT_FOUND => Error::NotFound(error),
+            StatusCode::BAD_REQUEST => Error::Validation(error),
+            StatusCode::CONFLICT => Error::Conflict(error),
+            StatusCode::UNAUTHORIZED => Error::Unauthorized(error),
+            StatusCode::FORBIDDEN => Error::Forbidden(error),
+            StatusCode::UNPROCESSABLE_ENTITY => Error::Unprocessable(error),
+            StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+                retry_after: error.retry_after_secs.map(Duration::from_secs),
+                body: error,
+            },
+            status if status.is_server_error() => Error::Internal(error),
+            status => Error::Unexpected {
+                status,
+                body: String::from_utf8_lossy(body).into_owned(),
+            },
+        }
+    }
+
+    /// the error body the service sent, if it sent one
+    pub fn body(&self) -> Option<&ErrorResponse> {
+        match self {
+            Error::NotFound(body)
+            | Error::Validation(body)
+            | Error::Conflict(body)
+            | Error::Unauthorized(body)
+            | Error::Forbidden(body)
+            | Error::Unprocessable(body)
+            | Error::RateLimited { body, .. }
+            | Error::Internal(body) => Some(body),
+            Error::Unexpected { .. } | Error::Transport(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound(body)
+            | Error::Validation(body)
+            | Error::Conflict(body)
+            | Error::Unauthorized(body)
+            | Error::Forbidden(body)
+            | Error::Unprocessable(body)
+            | Error::RateLimited { body, .. }
+            | Error::Internal(body) => match &body.request_id {
+                Some(request_id) => write!(f, "{} (request {})", body.error, request_id),
+                None => write!(f, "{}", body.error),
+            },
+            Error::Unexpected { status, body } => {
+                write!(f, "unexpected response ({}): {}", status, body)
+            }
+            Error::Transport(err) => write!(f, "request failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(err)
+    }
+}
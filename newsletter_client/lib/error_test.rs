@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::error::Error;
+    use reqwest::StatusCode;
+    use service::model::models::ErrorResponse;
+
+    #[test]
+    fn error_bodies_map_to_the_kind_of_error() {
+        // arrange
+        let body = br#"{"error": "resource not found: subscription", "request_id": "abc"}"#;
+
+        // act
+        let err = Error::from_response(StatusCode::NOT_FOUND, body);
+
+        // assert
+        assert!(matches!(err, Error::NotFound(_)));
+        assert_eq!(
+            Some(&ErrorResponse {
+                error: "resource not found: subscription".to_string(),
+                request_id: Some("abc".to_string()),
+                ..Default::default()
+            }),
+            err.body()
+        );
+        assert_eq!(
+            "resource not found: subscription (request abc)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn validation_errors_name_the_field() {
+        // arrange
+        let body = br#"{"error": "validation", "field": "email", "message": "invalid"}"#;
+
+        // act
+        let err = Error::from_response(StatusCode::BAD_REQUEST, body);
+
+        // assert
+        match err {
+            Error::Validation(body) => assert_eq!(Some("email".to_string()), body.field),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rate_limited_errors_carry_the_wait() {
+        // arrange
+        let body = br#"{"error": "too many requests", "retry_after_secs": 7}"#;
+
+        // act
+        let err = Error::from_response(StatusCode::TOO_MANY_REQUESTS, body);
+
+        // assert
+        match err {
+            Error::RateLimited { retry_after, .. } => {
+                assert_eq!(Some(Duration::from_secs(7)), retry_after)
+            }
+            other => panic!("expected a rate limited error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn responses_without_an_error_body_are_unexpected() {
+        // arrange
+        let body = b"<html>bad gateway</html>";
+
+        // act
+        let err = Error::from_response(StatusCode::BAD_GATEWAY, body);
+
+        // assert
+        match err {
+            Error::Unexpected { status, body } => {
+                assert_eq!(StatusCode::BAD_GATEWAY, status);
+                assert_eq!("<html>bad gateway</html>", body);
+            }
+            other => panic!("expected an unexpected response, got {:?}", other),
+        }
+    }
+}
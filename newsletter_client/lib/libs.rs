@@ -0,0 +1,8 @@
+mod client;
+mod error;
+mod error_test;
+
+pub use client::Client;
+pub use error::Error;
+/// the request and response bodies, shared with the service
+pub use service::model::models;
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+    use std::sync::Once;
+
+    use dotenvy::dotenv;
+    use newsletter_client::models::{
+        ApiKeyRole, CreateNewsletterRequest, CreateSubscriptionRequest, ListSubscriptionsRequest,
+        Newsletter, SubscriptionStatus,
+    };
+    use newsletter_client::{Client, Error};
+    use service::api;
+    use tokio::net::TcpListener;
+    use uuid::Uuid;
+
+    static ENV: Once = Once::new();
+
+    /// the service's `.env` and settings, which live a directory up
+    fn load_env() {
+        ENV.call_once(|| {
+            dotenv().ok();
+            if std::env::var_os("APP_CONFIG_DIR").is_none() {
+                let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../configuration");
+                std::env::set_var("APP_CONFIG_DIR", dir);
+            }
+        });
+    }
+
+    /// serves the router from `Settings::load` on a free local port, returning its url
+    async fn spawn_app() -> String {
+        load_env();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = api::app().into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    async fn admin_key() -> String {
+        load_env();
+        let settings = api::Settings::load().unwrap();
+        api::create_api_key(&settings, "test-newsletter_client", ApiKeyRole::Admin)
+            .await
+            .unwrap()
+            .1
+    }
+
+    /// the client has no newsletter methods, so one is created by hand
+    async fn create_newsletter(base_url: &str, api_key: &str) -> Newsletter {
+        reqwest::Client::new()
+            .post(format!("{}/v1/newsletters", base_url))
+            .bearer_auth(api_key)
+            .json(&CreateNewsletterRequest {
+                name: format!("newsletter-{}", Uuid::new_v4()),
+                description: "".to_string(),
+            })
+            .send()
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    fn unique_email() -> String {
+        format!("{}@example.com", Uuid::new_v4().simple())
+    }
+
+    #[tokio::test]
+    async fn subscribe_list_and_remove() {
+        // arrange
+        let base_url = spawn_app().await;
+        let api_key = admin_key().await;
+        let newsletter = create_newsletter(&base_url, &api_key).await;
+        let client = Client::new(&base_url).with_api_key(&api_key);
+        let email = unique_email();
+        let filter = ListSubscriptionsRequest {
+            email: Some(email.clone()),
+            ..Default::default()
+        };
+
+        // act
+        client
+            .subscribe(&CreateSubscriptionRequest {
+                email: email.clone(),
+                name: "Ursula".to_string(),
+                newsletter_id: newsletter.newsletter_id.clone(),
+            })
+            .await
+            .unwrap();
+        let listed = client.list(&filter).await.unwrap();
+        let removed = client
+            .remove(&listed.subscriptions[0].subscription_id)
+            .await
+            .unwrap();
+        let after = client.list(&filter).await.unwrap();
+
+        // assert
+        assert_eq!(1, listed.total);
+        assert_eq!(
+            SubscriptionStatus::PendingConfirmation,
+            listed.subscriptions[0].status
+        );
+        assert_eq!(newsletter.newsletter_id, removed.subscription.newsletter_id);
+        assert_eq!(
+            listed.subscriptions[0].subscription_id,
+            removed.subscription.subscription_id
+        );
+        assert_eq!(0, after.total);
+    }
+
+    #[tokio::test]
+    async fn confirm_with_an_unknown_token_is_not_found() {
+        // arrange
+        let client = Client::new(&spawn_app().await);
+
+        // act
+        let res = client.confirm(&Uuid::new_v4().simple().to_string()).await;
+
+        // assert
+        match res {
+            Err(Error::NotFound(body)) => assert!(body.request_id.is_some()),
+            other => panic!("expected not found, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn error_bodies_become_typed_errors() {
+        // arrange
+        let client = Client::new(&spawn_app().await);
+
+        // act
+        let invalid = client
+            .subscribe(&CreateSubscriptionRequest {
+                email: "not-an-email".to_string(),
+                name: "Ursula".to_string(),
+                newsletter_id: Uuid::new_v4().to_string(),
+            })
+            .await;
+        let without_key = client.list(&ListSubscriptionsRequest::default()).await;
+        let unknown_key = client
+            .clone()
+            .with_api_key("nl_unknown")
+            .remove(&Uuid::new_v4().to_string())
+            .await;
+
+        // assert
+        match invalid {
+            Err(Error::Validation(body)) => assert_eq!(Some("email".to_string()), body.field),
+            other => panic!("expected a validation error, got {:?}", other.map(|_| ())),
+        }
+        assert!(matches!(without_key, Err(Error::Unauthorized(_))));
+        assert!(matches!(unknown_key, Err(Error::Unauthorized(_))));
+    }
+}
@@ -1,16 +1,88 @@
-use std::{env, net::SocketAddr};
+use std::{env, process};
 
 use service::api;
 
 #[tokio::main]
 async fn main() {
-    let app = api::app();
-    let app_port: u16 = env::var("APP_PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(3000);
-    let addr = SocketAddr::from(([0, 0, 0, 0], app_port));
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    let settings = api::Settings::load().unwrap_or_else(|err| {
+        eprintln!("refusing to start, {}", err);
+        process::exit(1);
+    });
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("migrate") => {
+            migrate(&settings, args.get(1).map(String::as_str));
+            return;
+        }
+        Some("api-key") => {
+            let args: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+            api_key(&settings, &args).await;
+            return;
+        }
+        _ => {}
+    }
+
+    let shutdown = api::Shutdown::new();
+    let repo = api::repository(&settings);
+    let app = api::app_with_repository(&settings, repo.clone(), shutdown.clone());
+    let worker = api::spawn_worker(&settings, repo, shutdown.clone());
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            api::shutdown_signal().await;
+            shutdown.trigger();
+        }
+    });
+
+    let listener = tokio::net::TcpListener::bind(settings.server.address())
+        .await
+        .unwrap();
     tracing::debug!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.unwrap();
+    api::serve(listener, app, &settings.server, shutdown, vec![worker])
+        .await
+        .unwrap();
+}
+
+/// `newsletter_service migrate up|down|status`
+fn migrate(settings: &api::Settings, command: Option<&str>) {
+    let command = command
+        .ok_or_else(|| "missing migrate command".to_string())
+        .and_then(str::parse::<api::MigrationCommand>);
+    let command = match command {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("usage: newsletter_service migrate up|down|status");
+            process::exit(2);
+        }
+    };
+    match api::migrate(settings, command) {
+        Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
+
+/// `newsletter_service api-key create <name> <role>|revoke <id>|list`
+async fn api_key(settings: &api::Settings, args: &[&str]) {
+    let command = match api::ApiKeyCommand::parse(args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!("usage: newsletter_service api-key create <name> admin|publisher|read_only");
+            eprintln!("       newsletter_service api-key revoke <id>");
+            eprintln!("       newsletter_service api-key list");
+            process::exit(2);
+        }
+    };
+    match api::api_key(settings, command).await {
+        Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
 }
@@ -1,27 +1,151 @@
 #[cfg(test)]
 pub mod helper_functions {
     use axum::body::Body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::Router;
     use bytes::Bytes;
+    use diesel::{Connection, PgConnection, RunQueryDsl};
+    use fake::{faker::internet::en::SafeEmail, Fake};
     use futures_util::stream::StreamExt;
+    use hmac::{Hmac, Mac};
     use serde::Deserialize;
+    use service::api;
     use service::model::models::{
-        CreateSubscriptionRequest, GetSubscriptionRequest, RemoveSubscriptionRequest,
+        ApiKey, ApiKeyRole, CreateNewsletterRequest, CreateSubscriptionRequest,
+        GetSubscriptionRequest, Newsletter, RemoveSubscriptionRequest,
     };
+    use sha2::Sha256;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use tokio::sync::OnceCell;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
     pub fn new_create_subscription_request(
         name: String,
         email: String,
+        newsletter_id: String,
     ) -> CreateSubscriptionRequest {
-        CreateSubscriptionRequest { email, name }
+        CreateSubscriptionRequest {
+            email,
+            name,
+            newsletter_id,
+        }
     }
 
-    pub fn new_get_subscription_request(email: String) -> GetSubscriptionRequest {
-        GetSubscriptionRequest { email }
+    /// fake address made unique, as tests share one database and the faker's pool is small
+    pub fn unique_email() -> String {
+        format!(
+            "{}.{}",
+            Uuid::new_v4().simple(),
+            SafeEmail().fake::<String>()
+        )
+    }
+
+    static ADMIN_KEY: OnceCell<String> = OnceCell::const_new();
+
+    /// stores a fresh key with `role` in the database from `Settings::load`
+    pub async fn mint_api_key(role: ApiKeyRole) -> (ApiKey, String) {
+        dotenvy::dotenv().ok();
+        let settings = api::Settings::load().unwrap();
+        api::create_api_key(&settings, &format!("test-{}", role.as_str()), role)
+            .await
+            .unwrap()
+    }
+
+    /// `Authorization` header value with an admin key, minted once per test binary
+    pub async fn admin_authorization() -> String {
+        let key = ADMIN_KEY
+            .get_or_init(|| async { mint_api_key(ApiKeyRole::Admin).await.1 })
+            .await;
+        format!("Bearer {}", key)
+    }
+
+    pub fn new_create_newsletter_request(name: String) -> CreateNewsletterRequest {
+        CreateNewsletterRequest {
+            name,
+            description: "".to_string(),
+        }
+    }
+
+    /// creates a newsletter with a unique name through the router
+    pub async fn create_newsletter(app: &Router) -> Newsletter {
+        let payload = new_create_newsletter_request(format!("newsletter-{}", Uuid::new_v4()));
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/newsletters")
+            .header(header::AUTHORIZATION, admin_authorization().await)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&payload).unwrap()))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(StatusCode::CREATED, response.status());
+        get_response(response.into_body()).await.unwrap()
+    }
+
+    /// makes the subscriptions of `email` look like ones made before subscribers gave names
+    pub fn clear_subscriber_name(email: &str) {
+        let url = std::env::var("DATABASE_URL").unwrap();
+        let mut conn = PgConnection::establish(&url).unwrap();
+        diesel::sql_query(format!(
+            "UPDATE subscriptions SET subscriber_name = NULL WHERE lower(email) = lower('{}')",
+            email
+        ))
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    /// holds a row lock on the newsletter from a separate connection until told to stop
+    pub fn lock_newsletter(newsletter_id: &str) -> (mpsc::Sender<()>, std::thread::JoinHandle<()>) {
+        let url = std::env::var("DATABASE_URL").unwrap();
+        let newsletter_id = newsletter_id.to_string();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let handle = std::thread::spawn(move || {
+            let mut conn = PgConnection::establish(&url).unwrap();
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                diesel::sql_query(format!(
+                    "SELECT id FROM newsletters WHERE id = '{}' FOR UPDATE",
+                    newsletter_id
+                ))
+                .execute(conn)?;
+                locked_tx.send(()).unwrap();
+                let _ = release_rx.recv_timeout(Duration::from_secs(10));
+                Ok(())
+            })
+            .unwrap();
+        });
+        locked_rx.recv().unwrap();
+        (release_tx, handle)
+    }
+
+    pub fn new_get_subscription_request(
+        email: String,
+        include_pending: bool,
+    ) -> GetSubscriptionRequest {
+        GetSubscriptionRequest {
+            email,
+            include_pending,
+        }
     }
 
     pub fn new_remove_subscription_request(subscription_id: String) -> RemoveSubscriptionRequest {
         RemoveSubscriptionRequest { subscription_id }
     }
 
+    /// signs an unsubscribe token the same way the service does, using `UNSUBSCRIBE_SECRET`
+    pub fn unsubscribe_token(subscription_id: &str) -> String {
+        let subscription_id = Uuid::parse_str(subscription_id).unwrap();
+        let secret = std::env::var("UNSUBSCRIBE_SECRET").unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(subscription_id.as_bytes());
+        format!(
+            "{}.{}",
+            subscription_id.simple(),
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+
     pub async fn body_to_bytes(body: Body) -> Result<bytes::Bytes, axum::Error> {
         let mut bytes = Vec::new();
         let mut stream = body.into_data_stream();
@@ -2,7 +2,9 @@
 mod test {
     use crate::common::helper::helper_functions;
     use fake::{faker::internet::en::SafeEmail, Fake};
+    use service::model::models::Newsletter;
     use std::env;
+    use uuid::Uuid;
 
     fn get_base_url() -> String {
         let password = env::var("BACKEND_URL");
@@ -15,14 +17,32 @@ mod test {
         // arrange
         dotenvy::dotenv().ok();
         let email: String = SafeEmail().fake();
-        let subscription = "new_york_times".to_string();
-        let payload =
-            helper_functions::new_create_subscription_request(subscription.clone(), email);
         let base_url = get_base_url();
         let client = reqwest::Client::new();
+        let newsletter: Newsletter = client
+            .post(format!("{}/v1/newsletters", base_url))
+            .header(
+                "Authorization",
+                helper_functions::admin_authorization().await,
+            )
+            .json(&helper_functions::new_create_newsletter_request(format!(
+                "newsletter-{}",
+                Uuid::new_v4()
+            )))
+            .send()
+            .await
+            .expect("Failed to create newsletter")
+            .json()
+            .await
+            .unwrap();
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email,
+            newsletter.newsletter_id,
+        );
         // act
         let response = client
-            .post(format!("{}/subscribe", base_url))
+            .post(format!("{}/v1/subscribe", base_url))
             .header("Content-Type", "application/json")
             .json(&payload)
             .send()
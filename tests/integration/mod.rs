@@ -1,5 +1,16 @@
 #[path = "../common/mod.rs"]
 pub mod common;
+mod test_auth;
 mod test_echo_endpoint;
 mod test_health_check;
+mod test_idempotency;
+mod test_issues;
+mod test_jobs;
+mod test_load;
+mod test_metrics;
+mod test_newsletters;
+mod test_openapi;
+mod test_rate_limit;
+mod test_request_id;
+mod test_shutdown;
 mod test_subscription;
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod auth_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use axum::Router;
+    use dotenvy::dotenv;
+    use service::api;
+    use service::model::models::ApiKeyRole;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn create_newsletter(app: &Router, key: Option<&str>) -> StatusCode {
+        let payload = helper_functions::new_create_newsletter_request(format!(
+            "newsletter-{}",
+            Uuid::new_v4()
+        ));
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/newsletters")
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            req = req.header(header::AUTHORIZATION, format!("Bearer {}", key));
+        }
+        let req = req
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn roles_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let (_, read_only) = helper_functions::mint_api_key(ApiKeyRole::ReadOnly).await;
+        let (_, publisher) = helper_functions::mint_api_key(ApiKeyRole::Publisher).await;
+
+        // act
+        let anonymous = create_newsletter(&app, None).await;
+        let unknown = create_newsletter(&app, Some("nl_unknown")).await;
+        let forbidden = create_newsletter(&app, Some(&read_only)).await;
+        let created = create_newsletter(&app, Some(&publisher)).await;
+
+        // assert
+        assert_eq!(StatusCode::UNAUTHORIZED, anonymous);
+        assert_eq!(StatusCode::UNAUTHORIZED, unknown);
+        assert_eq!(StatusCode::FORBIDDEN, forbidden);
+        assert_eq!(StatusCode::CREATED, created);
+    }
+
+    #[tokio::test]
+    async fn revoked_key_test() {
+        // arrange
+        dotenv().ok();
+        let settings = api::Settings::load().unwrap();
+        let app = api::app();
+        let (record, key) = helper_functions::mint_api_key(ApiKeyRole::Publisher).await;
+        let id = Uuid::parse_str(&record.api_key_id).unwrap();
+
+        // act
+        let before = create_newsletter(&app, Some(&key)).await;
+        api::api_key(&settings, api::ApiKeyCommand::Revoke(id))
+            .await
+            .unwrap();
+        let after = create_newsletter(&app, Some(&key)).await;
+
+        // assert
+        assert_eq!(StatusCode::CREATED, before);
+        assert_eq!(StatusCode::UNAUTHORIZED, after);
+    }
+}
@@ -12,7 +12,7 @@ async fn echo_endpoint() {
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/echo?name=ydot19")
+                .uri("/v1/echo?name=ydot19")
                 .method(Method::GET)
                 .body(axum::body::Body::empty())
                 .unwrap(),
@@ -1,27 +1,24 @@
 use axum::http::{Method, Request, StatusCode};
+use dotenvy::dotenv;
 use http_body_util::BodyExt;
 use service::api;
+use service::model::models::{HealthResponse, HealthStatus};
 use tower::ServiceExt;
 
-#[tokio::test]
-async fn health_check_endpoint() {
-    // arrange
+async fn get(uri: &str) -> (StatusCode, String) {
+    dotenv().ok();
     let app = api::app();
-
-    // act
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/health_check")
+                .uri(uri)
                 .method(Method::GET)
                 .body(axum::body::Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
-
-    // assert
-    assert_eq!(StatusCode::OK, response.status());
+    let status = response.status();
     let b = response
         .into_body()
         .collect()
@@ -29,6 +26,57 @@ async fn health_check_endpoint() {
         .unwrap()
         .to_bytes()
         .to_vec();
-    let bs = String::from_utf8(b).unwrap();
+    (status, String::from_utf8(b).unwrap())
+}
+
+#[tokio::test]
+async fn health_check_endpoint() {
+    // act
+    let (status, bs) = get("/health_check").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, status);
     assert!(bs.contains("healthy"));
 }
+
+#[tokio::test]
+async fn liveness_endpoint() {
+    // act
+    let (status, bs) = get("/health/live").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, status);
+    let health: HealthResponse = serde_json::from_str(&bs).unwrap();
+    assert_eq!(HealthStatus::Healthy, health.status);
+    assert!(health.checks.is_empty());
+}
+
+#[tokio::test]
+async fn readiness_endpoint() {
+    // act
+    let (status, bs) = get("/health/ready").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, status, "{}", bs);
+    let health: HealthResponse = serde_json::from_str(&bs).unwrap();
+    assert_eq!(HealthStatus::Healthy, health.status);
+    for check in &health.checks {
+        assert_eq!(HealthStatus::Healthy, check.status, "{}", check.name);
+    }
+    let database = health.checks.iter().find(|c| c.name == "database").unwrap();
+    assert!(database.details["latency_ms"].is_u64());
+    let migrations = health.checks.iter().find(|c| c.name == "migrations");
+    assert_eq!(
+        Some(0),
+        migrations.map(|c| c.details["pending"].as_array().unwrap().len())
+    );
+}
+
+#[tokio::test]
+async fn health_check_is_not_versioned() {
+    // act
+    let (status, _) = get("/v1/health_check").await;
+
+    // assert
+    assert_eq!(StatusCode::NOT_FOUND, status);
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod idempotency_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::response::Response;
+    use axum::Router;
+    use dotenvy::dotenv;
+    use http_body_util::BodyExt;
+    use service::api;
+    use service::model::models::GetSubscriptionsResponse;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        key: Option<&str>,
+        payload: &serde_json::Value,
+    ) -> Response {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            req = req.header("idempotency-key", key);
+        }
+        let req = req
+            .body(body::Body::from(serde_json::to_string(payload).unwrap()))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    async fn send_anonymous(
+        app: &Router,
+        key: &str,
+        forwarded_for: &str,
+        payload: &serde_json::Value,
+    ) -> Response {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("idempotency-key", key)
+            .header("x-forwarded-for", forwarded_for)
+            .body(body::Body::from(serde_json::to_string(payload).unwrap()))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    async fn into_text(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribe_replay_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = serde_json::to_value(helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            helper_functions::unique_email(),
+            newsletter.newsletter_id.clone(),
+        ))
+        .unwrap();
+        let key = Uuid::new_v4().to_string();
+        let first = send(&app, Method::POST, "/v1/subscribe", Some(&key), &payload).await;
+        assert_eq!(StatusCode::CREATED, first.status());
+        assert!(first.headers().get("idempotent-replayed").is_none());
+        let first_request_id = first.headers().get("x-request-id").unwrap().clone();
+        let first = into_text(first).await;
+
+        // act
+        let retried = send(&app, Method::POST, "/v1/subscribe", Some(&key), &payload).await;
+        let without_key = send(&app, Method::POST, "/v1/subscribe", None, &payload).await;
+        let mut changed = payload.clone();
+        changed["name"] = serde_json::json!("someone else");
+        let reused = send(&app, Method::POST, "/v1/subscribe", Some(&key), &changed).await;
+
+        // assert
+        assert_eq!(StatusCode::CREATED, retried.status());
+        assert_eq!(
+            "true",
+            retried.headers().get("idempotent-replayed").unwrap()
+        );
+        let retried_request_ids: Vec<_> =
+            retried.headers().get_all("x-request-id").iter().collect();
+        assert_eq!(1, retried_request_ids.len());
+        assert_ne!(&first_request_id, retried_request_ids[0]);
+        assert_eq!(first, into_text(retried).await);
+        assert_eq!(StatusCode::OK, without_key.status());
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, reused.status());
+    }
+
+    #[tokio::test]
+    async fn remove_replay_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let email = helper_functions::unique_email();
+        let payload = serde_json::to_value(helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email.clone(),
+            newsletter.newsletter_id,
+        ))
+        .unwrap();
+        let created = send(&app, Method::POST, "/v1/subscribe", None, &payload).await;
+        assert_eq!(StatusCode::CREATED, created.status());
+        let found = send(
+            &app,
+            Method::GET,
+            &format!("/v1/subscriptions?email={}&include_pending=true", email),
+            None,
+            &serde_json::Value::Null,
+        )
+        .await;
+        let found: GetSubscriptionsResponse = helper_functions::get_response(found.into_body())
+            .await
+            .unwrap();
+        let payload = serde_json::to_value(helper_functions::new_remove_subscription_request(
+            found.resp[0].subscription_id.clone(),
+        ))
+        .unwrap();
+        let key = Uuid::new_v4().to_string();
+
+        // act
+        let removed = send(&app, Method::DELETE, "/v1/subscribe", Some(&key), &payload).await;
+        let retried = send(&app, Method::DELETE, "/v1/subscribe", Some(&key), &payload).await;
+        let without_key = send(&app, Method::DELETE, "/v1/subscribe", None, &payload).await;
+
+        // assert
+        assert_eq!(StatusCode::NO_CONTENT, removed.status());
+        assert_eq!(StatusCode::NO_CONTENT, retried.status());
+        assert_eq!(
+            "true",
+            retried.headers().get("idempotent-replayed").unwrap()
+        );
+        assert_eq!("true", retried.headers().get("deprecation").unwrap());
+        assert_eq!(StatusCode::NOT_FOUND, without_key.status());
+    }
+
+    #[tokio::test]
+    async fn anonymous_replay_is_scoped_by_address_test() {
+        // arrange
+        dotenv().ok();
+        let mut settings = api::Settings::load().unwrap();
+        settings.rate_limit.trusted_proxy_hops = 1;
+        settings.rate_limit.subscribe.per_ip = None;
+        settings.rate_limit.subscribe.per_email = None;
+        let app = api::app_with_settings(&settings, api::Shutdown::new());
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = serde_json::to_value(helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            helper_functions::unique_email(),
+            newsletter.newsletter_id,
+        ))
+        .unwrap();
+        let key = Uuid::new_v4().to_string();
+        let first = send_anonymous(&app, &key, "192.0.2.30", &payload).await;
+        assert_eq!(StatusCode::CREATED, first.status());
+
+        // act
+        let retried = send_anonymous(&app, &key, "192.0.2.30", &payload).await;
+        let other_client = send_anonymous(&app, &key, "192.0.2.31", &payload).await;
+
+        // assert
+        assert_eq!(StatusCode::CREATED, retried.status());
+        assert_eq!(
+            "true",
+            retried.headers().get("idempotent-replayed").unwrap()
+        );
+        assert_eq!(StatusCode::OK, other_client.status());
+        assert!(other_client.headers().get("idempotent-replayed").is_none());
+    }
+
+    #[tokio::test]
+    async fn invalid_key_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+
+        // act
+        let response = send(
+            &app,
+            Method::POST,
+            "/v1/subscribe",
+            Some("not a key"),
+            &serde_json::json!({}),
+        )
+        .await;
+
+        // assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert!(into_text(response).await.contains("Idempotency-Key"));
+    }
+}
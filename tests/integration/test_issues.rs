@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod issue_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use dotenvy::dotenv;
+    use service::api;
+    use service::model::models::{CreateIssueRequest, Issue, IssueResponse};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn new_create_issue_request() -> CreateIssueRequest {
+        CreateIssueRequest {
+            title: "Issue #1".to_string(),
+            text_body: "hello".to_string(),
+            html_body: "<p>hello</p>".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_and_publish_issue_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = new_create_issue_request();
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!(
+                "/v1/newsletters/{}/issues",
+                newsletter.newsletter_id
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act - create
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CREATED, response.status());
+        let issue: Issue = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert_eq!(payload.title, issue.title);
+        assert!(issue.published_at.is_none());
+
+        // act - publish
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!(
+                "/v1/newsletters/{}/issues/{}/publish",
+                newsletter.newsletter_id, issue.issue_id
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::ACCEPTED, response.status());
+        let published: IssueResponse = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert_eq!(issue.issue_id, published.issue.issue_id);
+        assert_eq!(0, published.deliveries.sent);
+
+        // act - get
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!(
+                "/v1/newsletters/{}/issues/{}",
+                newsletter.newsletter_id, issue.issue_id
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn create_issue_unknown_newsletter_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let payload = new_create_issue_request();
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/v1/newsletters/{}/issues", Uuid::new_v4()))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn publish_unknown_issue_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!(
+                "/v1/newsletters/{}/issues/{}/publish",
+                newsletter.newsletter_id,
+                Uuid::new_v4()
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod job_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use dotenvy::dotenv;
+    use service::api;
+    use service::model::models::{GetJobsResponse, JobStatus};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn get_dead_jobs_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/jobs/dead")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let jobs: GetJobsResponse = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert!(jobs.resp.iter().all(|job| job.status == JobStatus::Dead));
+    }
+
+    #[tokio::test]
+    async fn requeue_unknown_job_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/v1/jobs/{}/requeue", Uuid::new_v4()))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn requeue_invalid_job_id_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/jobs/not-a-uuid/requeue")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+}
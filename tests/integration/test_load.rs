@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod load_tests {
+    use std::time::Duration;
+
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use axum::Router;
+    use dotenvy::dotenv;
+    use futures_util::future::join_all;
+    use service::api;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn subscribe(app: Router, newsletter_id: String) -> StatusCode {
+        let email = format!("load-{}@example.com", Uuid::new_v4());
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email,
+            newsletter_id,
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()))
+            .unwrap();
+        app.oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_subscribes_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+
+        // act
+        let requests =
+            (0..50).map(|_| tokio::spawn(subscribe(app.clone(), newsletter.newsletter_id.clone())));
+        let statuses = join_all(requests).await;
+
+        // assert
+        for status in statuses {
+            assert_eq!(StatusCode::CREATED, status.unwrap());
+        }
+    }
+
+    /// a request stuck in the database used to hold the repository lock and a runtime
+    /// thread, queueing every other request behind it
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn blocked_request_does_not_stall_others_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let locked = helper_functions::create_newsletter(&app).await;
+        let other = helper_functions::create_newsletter(&app).await;
+        let (release, lock_holder) = helper_functions::lock_newsletter(&locked.newsletter_id);
+        let payload =
+            serde_json::json!({"name": format!("renamed-{}", Uuid::new_v4()), "description": ""});
+        let req = Request::builder()
+            .method(Method::PUT)
+            .uri(format!("/v1/newsletters/{}", locked.newsletter_id))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(payload.to_string()))
+            .unwrap();
+        let blocked = tokio::spawn(app.clone().oneshot(req));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // act
+        let requests = (0..10).map(|_| {
+            let req = Request::builder()
+                .method(Method::GET)
+                .uri(format!("/v1/newsletters/{}", other.newsletter_id))
+                .body(body::Body::empty())
+                .unwrap();
+            tokio::spawn(app.clone().oneshot(req))
+        });
+        let responses = join_all(requests).await;
+
+        // assert
+        for response in responses {
+            assert_eq!(StatusCode::OK, response.unwrap().unwrap().status());
+        }
+        // had they queued behind it, it would have finished first
+        assert!(
+            !blocked.is_finished(),
+            "requests waited for the blocked one"
+        );
+        release.send(()).unwrap();
+        lock_holder.join().unwrap();
+        assert_eq!(StatusCode::OK, blocked.await.unwrap().unwrap().status());
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod metrics_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use axum::Router;
+    use dotenvy::dotenv;
+    use service::api;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn send(app: &Router, method: Method, uri: &str, payload: Option<String>) -> StatusCode {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(
+                payload
+                    .map(body::Body::from)
+                    .unwrap_or_else(body::Body::empty),
+            )
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap().status()
+    }
+
+    /// the value of the sample whose name and labels start with `series`
+    fn sample(metrics: &str, series: &str) -> Option<f64> {
+        metrics
+            .lines()
+            .find(|line| line.starts_with(series))
+            .and_then(|line| line.rsplit(' ').next())
+            .and_then(|value| value.parse().ok())
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            helper_functions::unique_email(),
+            newsletter.newsletter_id.clone(),
+        );
+        let created = send(
+            &app,
+            Method::POST,
+            "/v1/subscribe",
+            Some(serde_json::to_string(&payload).unwrap()),
+        )
+        .await;
+        let missing = send(
+            &app,
+            Method::GET,
+            &format!("/v1/newsletters/{}", Uuid::new_v4()),
+            None,
+        )
+        .await;
+        let invalid = send(&app, Method::GET, "/v1/newsletters/not-a-uuid", None).await;
+
+        // act
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/metrics")
+            .body(body::Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CREATED, created);
+        assert_eq!(StatusCode::NOT_FOUND, missing);
+        assert_eq!(StatusCode::BAD_REQUEST, invalid);
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let bytes = helper_functions::body_to_bytes(response.into_body())
+            .await
+            .unwrap();
+        let metrics = String::from_utf8(bytes.to_vec()).unwrap();
+        assert_eq!(
+            Some(1.0),
+            sample(
+                &metrics,
+                r#"http_requests_total{method="POST",path="/v1/subscribe",status="201"}"#
+            ),
+            "{}",
+            metrics
+        );
+        for status in ["400", "404"] {
+            let series = format!(
+                r#"http_requests_total{{method="GET",path="/v1/newsletters/:newsletter_id",status="{}"}}"#,
+                status
+            );
+            assert_eq!(Some(1.0), sample(&metrics, &series), "{}", metrics);
+        }
+        assert!(metrics.contains("http_request_duration_seconds_bucket{"));
+        assert_eq!(Some(1.0), sample(&metrics, "subscriptions_created_total "));
+        assert_eq!(Some(0.0), sample(&metrics, "subscriptions_removed_total "));
+        assert_eq!(
+            Some(1.0),
+            sample(&metrics, r#"domain_errors_total{variant="not_found"}"#)
+        );
+        assert_eq!(
+            Some(1.0),
+            sample(&metrics, r#"domain_errors_total{variant="validation"}"#)
+        );
+        assert!(sample(&metrics, "db_pool_max_size ").unwrap() >= 1.0);
+        assert!(sample(&metrics, "db_pool_connections ").is_some());
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod newsletter_integration_tests {
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::StatusCode;
+    use axum::http::{header, Method, Request};
+    use dotenvy::dotenv;
+    use service::api;
+    use service::model::models::{
+        GetNewslettersResponse, Newsletter, RemoveNewsletterResponse, UpdateNewsletterRequest,
+    };
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn newsletter_crud_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let uri = format!("/v1/newsletters/{}", newsletter.newsletter_id);
+
+        // act - get
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(uri.clone())
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let fetched: Newsletter = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert_eq!(newsletter.name, fetched.name);
+
+        // act - list
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/newsletters")
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let listed: GetNewslettersResponse = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert!(listed
+            .resp
+            .iter()
+            .any(|n| n.newsletter_id == newsletter.newsletter_id));
+
+        // act - update
+        let payload = UpdateNewsletterRequest {
+            name: format!("{}-renamed", newsletter.name),
+            description: "weekly".to_string(),
+        };
+        let req = Request::builder()
+            .method(Method::PUT)
+            .uri(uri.clone())
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let updated: Newsletter = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert_eq!(payload.name, updated.name);
+
+        // act - remove
+        let req = Request::builder()
+            .method(Method::DELETE)
+            .uri(uri.clone())
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let removed: RemoveNewsletterResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        assert_eq!(newsletter.newsletter_id, removed.newsletter.newsletter_id);
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn create_newsletter_empty_name_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let payload = helper_functions::new_create_newsletter_request("  ".to_string());
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/newsletters")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+
+    #[tokio::test]
+    async fn create_newsletter_duplicate_name_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = helper_functions::new_create_newsletter_request(newsletter.name);
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/newsletters")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CONFLICT, response.status());
+    }
+
+    #[tokio::test]
+    async fn get_newsletter_not_found_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/newsletters/{}", Uuid::new_v4()))
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+}
@@ -0,0 +1,63 @@
+use axum::http::{Method, Request, StatusCode};
+use dotenvy::dotenv;
+use http_body_util::BodyExt;
+use service::api;
+use tower::ServiceExt;
+
+async fn get(uri: &str) -> axum::response::Response {
+    dotenv().ok();
+    api::app()
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .method(Method::GET)
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn openapi_document() {
+    // act
+    let response = get("/v1/openapi.json").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, response.status());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert_eq!("/v1", spec["servers"][0]["url"]);
+    assert!(spec["paths"]["/subscribe"]["post"].is_object());
+    assert_eq!(true, spec["paths"]["/subscribe"]["delete"]["deprecated"]);
+    assert!(spec["paths"]["/unsubscribe"]["get"].is_object());
+    assert!(spec["paths"]["/unsubscribe"]["post"].is_object());
+    assert_eq!("/", spec["paths"]["/health/ready"]["servers"][0]["url"]);
+    assert!(spec["components"]["schemas"]["CreateSubscriptionRequest"].is_object());
+    assert!(spec["components"]["securitySchemes"]["api_key"].is_object());
+}
+
+#[tokio::test]
+async fn unprefixed_alias_is_deprecated() {
+    // act
+    let unversioned = get("/openapi.json").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, unversioned.status());
+    assert_eq!("true", unversioned.headers()["deprecation"]);
+    assert_eq!(
+        "</v1/openapi.json>; rel=\"successor-version\"",
+        unversioned.headers()["link"]
+    );
+}
+
+#[cfg(feature = "swagger-ui")]
+#[tokio::test]
+async fn swagger_ui() {
+    // act
+    let response = get("/v1/swagger-ui/").await;
+
+    // assert
+    assert_eq!(StatusCode::OK, response.status());
+}
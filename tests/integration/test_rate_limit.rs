@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod rate_limit_tests {
+    use std::time::Duration;
+
+    use crate::common::helper::helper_functions;
+    use axum::body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::Router;
+    use dotenvy::dotenv;
+    use service::api;
+    use service::api::Quota;
+    use tower::ServiceExt;
+
+    fn quota(capacity: u32) -> Option<Quota> {
+        Some(Quota {
+            capacity,
+            refill: Duration::from_secs(600),
+        })
+    }
+
+    async fn subscribe(
+        app: &Router,
+        email: &str,
+        newsletter_id: &str,
+        forwarded_for: &str,
+    ) -> axum::response::Response {
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email.to_string(),
+            newsletter_id.to_string(),
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-forwarded-for", forwarded_for)
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn per_email_limit_test() {
+        // arrange
+        dotenv().ok();
+        let mut settings = api::Settings::load().unwrap();
+        settings.rate_limit.trusted_proxy_hops = 1;
+        settings.rate_limit.subscribe.per_ip = None;
+        settings.rate_limit.subscribe.per_email = quota(2);
+        let app = api::app_with_settings(&settings, api::Shutdown::new());
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let email = helper_functions::unique_email();
+
+        // act - every attempt comes from another client
+        let first = subscribe(&app, &email, &newsletter.newsletter_id, "192.0.2.1").await;
+        let resent = subscribe(&app, &email, &newsletter.newsletter_id, "192.0.2.2").await;
+        let limited = subscribe(
+            &app,
+            &email.to_uppercase(),
+            &newsletter.newsletter_id,
+            "192.0.2.3",
+        )
+        .await;
+        let other = subscribe(
+            &app,
+            &helper_functions::unique_email(),
+            &newsletter.newsletter_id,
+            "192.0.2.4",
+        )
+        .await;
+
+        // assert
+        assert_eq!(StatusCode::CREATED, first.status());
+        assert_eq!(StatusCode::OK, resent.status());
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, limited.status());
+        assert_eq!("600", limited.headers().get(header::RETRY_AFTER).unwrap());
+        let body: serde_json::Value = helper_functions::get_response(limited.into_body())
+            .await
+            .unwrap();
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("too many requests"));
+        assert_eq!(600, body["retry_after_secs"]);
+        assert_eq!(StatusCode::CREATED, other.status());
+    }
+
+    #[tokio::test]
+    async fn per_ip_limit_test() {
+        // arrange
+        dotenv().ok();
+        let mut settings = api::Settings::load().unwrap();
+        settings.rate_limit.trusted_proxy_hops = 1;
+        settings.rate_limit.subscribe.per_ip = quota(1);
+        settings.rate_limit.subscribe.per_email = None;
+        let app = api::app_with_settings(&settings, api::Shutdown::new());
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let id = newsletter.newsletter_id.as_str();
+
+        // act - the client cannot escape its limit by forging the entries before the proxy's
+        let first = subscribe(&app, &helper_functions::unique_email(), id, "192.0.2.10").await;
+        let limited = subscribe(
+            &app,
+            &helper_functions::unique_email(),
+            id,
+            "198.51.100.1, 192.0.2.10",
+        )
+        .await;
+        let other = subscribe(&app, &helper_functions::unique_email(), id, "192.0.2.11").await;
+
+        // assert
+        assert_eq!(StatusCode::CREATED, first.status());
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, limited.status());
+        assert!(limited.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(StatusCode::CREATED, other.status());
+    }
+
+    #[tokio::test]
+    async fn per_peer_address_test() {
+        // arrange
+        dotenv().ok();
+        let mut settings = api::Settings::load().unwrap();
+        settings.rate_limit.trusted_proxy_hops = 0;
+        settings.rate_limit.unsubscribe.per_ip = quota(1);
+        let app = api::app_with_settings(&settings, api::Shutdown::new());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let shutdown = api::Shutdown::new();
+        let server = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                api::serve(listener, app, &settings.server, shutdown, Vec::new()).await
+            })
+        };
+        let client = reqwest::Client::new();
+        let unsubscribe = || {
+            client
+                .get(format!("{}/v1/unsubscribe?token=invalid", base_url))
+                .header("x-forwarded-for", "192.0.2.20")
+                .send()
+        };
+
+        // act - a forwarded address is not trusted without proxies in front
+        let first = unsubscribe().await.unwrap();
+        let limited = unsubscribe().await.unwrap();
+        shutdown.trigger();
+        let _ = server.await;
+
+        // assert
+        assert_eq!(reqwest::StatusCode::BAD_REQUEST, first.status());
+        assert_eq!(reqwest::StatusCode::TOO_MANY_REQUESTS, limited.status());
+    }
+}
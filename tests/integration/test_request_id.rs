@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod request_id_integration_tests {
+    use axum::body;
+    use axum::http::{Method, Request, StatusCode};
+    use dotenvy::dotenv;
+    use service::api;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::common::helper::helper_functions;
+
+    #[tokio::test]
+    async fn generates_request_id_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/health/live")
+            .body(body::Body::empty())
+            .unwrap();
+
+        // act
+        let response = app.oneshot(req).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let id = response.headers()[api::REQUEST_ID_HEADER].to_str().unwrap();
+        assert!(Uuid::parse_str(id).is_ok(), "{}", id);
+    }
+
+    #[tokio::test]
+    async fn error_body_carries_request_id_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/newsletters/not-a-uuid")
+            .header(api::REQUEST_ID_HEADER, "client-chosen-id")
+            .body(body::Body::empty())
+            .unwrap();
+
+        // act
+        let response = app.oneshot(req).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!(
+            "client-chosen-id",
+            response.headers()[api::REQUEST_ID_HEADER]
+        );
+        let body: serde_json::Value = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        assert_eq!("client-chosen-id", body["request_id"]);
+        assert_eq!("newsletter_id", body["field"]);
+    }
+}
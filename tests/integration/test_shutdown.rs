@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod shutdown_tests {
+    use std::time::Duration;
+
+    use crate::common::helper::helper_functions;
+    use dotenvy::dotenv;
+    use reqwest::StatusCode;
+    use service::api;
+    use uuid::Uuid;
+
+    /// an in-flight request is allowed to finish after shutdown starts, while readiness
+    /// already reports unhealthy so the load balancer stops routing new traffic
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn drains_in_flight_requests_test() {
+        // arrange
+        dotenv().ok();
+        let mut settings = api::Settings::load().unwrap();
+        settings.server.shutdown_delay = Duration::from_secs(1);
+        settings.server.drain_timeout = Duration::from_secs(10);
+        let shutdown = api::Shutdown::new();
+        let app = api::app_with_settings(&settings, shutdown.clone());
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                api::serve(listener, app, &settings.server, shutdown, Vec::new()).await
+            })
+        };
+        let client = reqwest::Client::new();
+        let authorization = helper_functions::admin_authorization().await;
+        let (release, lock_holder) = helper_functions::lock_newsletter(&newsletter.newsletter_id);
+        let in_flight = tokio::spawn(
+            client
+                .put(format!("{}/v1/newsletters/{}", base_url, newsletter.newsletter_id))
+                .header("authorization", authorization)
+                .json(&serde_json::json!({"name": format!("renamed-{}", Uuid::new_v4()), "description": ""}))
+                .send(),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // act
+        shutdown.trigger();
+        let ready = client
+            .get(format!("{}/health/ready", base_url))
+            .send()
+            .await
+            .unwrap();
+        release.send(()).unwrap();
+        lock_holder.join().unwrap();
+        let in_flight = in_flight.await.unwrap().unwrap();
+        let stopped = tokio::time::timeout(Duration::from_secs(15), server).await;
+
+        // assert
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, ready.status());
+        assert_eq!(StatusCode::OK, in_flight.status());
+        assert!(stopped.expect("server did not stop").unwrap().is_ok());
+        assert!(client
+            .get(format!("{}/health/live", base_url))
+            .send()
+            .await
+            .is_err());
+    }
+}
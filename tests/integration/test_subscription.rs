@@ -7,11 +7,11 @@ mod subscription_integration_tests {
     use axum::http::StatusCode;
     use axum::http::{header, Method, Request};
     use dotenvy::dotenv;
-    use fake::{faker::internet::en::SafeEmail, Fake};
     use http_body_util::BodyExt;
     use service::api;
     use service::model::models::{
-        GetSubscriptionsResponse, RemoveSubscriptionResponse, Subscription,
+        GetSubscriptionsResponse, ListSubscriptionsResponse, RemoveSubscriptionResponse,
+        Subscription, UnsubscribePreviewResponse,
     };
     use tower::ServiceExt;
     use uuid::Uuid;
@@ -21,13 +21,17 @@ mod subscription_integration_tests {
         // arrange
         dotenv().ok();
         let app = api::app();
-        let email: String = SafeEmail().fake();
-        let subscription = "new_york_times".to_string();
-        let payload =
-            helper_functions::new_create_subscription_request(subscription.clone(), email);
+        let email: String = helper_functions::unique_email();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let subscription = newsletter.name.clone();
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email,
+            newsletter.newsletter_id,
+        );
         let req = Request::builder()
             .method(Method::POST)
-            .uri("/subscribe")
+            .uri("/v1/subscribe")
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
 
@@ -50,16 +54,73 @@ mod subscription_integration_tests {
         assert!(response_body.contains(&subscription))
     }
 
+    #[tokio::test]
+    async fn subscription_on_unprefixed_alias_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            helper_functions::unique_email(),
+            newsletter.newsletter_id,
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!("true", response.headers()["deprecation"]);
+        assert_eq!(
+            "</v1/subscribe>; rel=\"successor-version\"",
+            response.headers()[header::LINK]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscription_unknown_newsletter_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let email: String = helper_functions::unique_email();
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email,
+            Uuid::new_v4().to_string(),
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
     #[tokio::test]
     async fn get_subscriptions_not_found_test() {
         // arrange
         dotenv().ok();
-        let fake_email: String = SafeEmail().fake();
+        let fake_email: String = helper_functions::unique_email();
         let app = api::app();
-        let payload = helper_functions::new_get_subscription_request(fake_email.clone());
+        let payload = helper_functions::new_get_subscription_request(fake_email.clone(), false);
         let req = Request::builder()
             .method(Method::GET)
-            .uri("/subscriptions")
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
         // act
@@ -67,6 +128,7 @@ mod subscription_integration_tests {
 
         // assert
         assert_eq!(StatusCode::NOT_FOUND, response.status());
+        assert_eq!("true", response.headers()["deprecation"]);
         let body = helper_functions::body_to_bytes(response.into_body())
             .await
             .unwrap();
@@ -82,7 +144,11 @@ mod subscription_integration_tests {
             helper_functions::new_remove_subscription_request("not_uuid".to_string());
         let req = Request::builder()
             .method(Method::DELETE)
-            .uri("/subscribe")
+            .uri("/v1/subscribe")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(
                 serde_json::to_string(&remove_subscription_request).unwrap(),
@@ -101,32 +167,167 @@ mod subscription_integration_tests {
             helper_functions::new_remove_subscription_request(subscription_id.to_string());
         let req = Request::builder()
             .method(Method::DELETE)
-            .uri("/subscribe")
+            .uri("/v1/subscribe")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(
                 serde_json::to_string(&remove_subscription_request).unwrap(),
             ));
 
         let response = app.clone().oneshot(req.unwrap()).await.unwrap();
-        assert_eq!(StatusCode::NOT_FOUND, response.status())
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        assert_eq!("true", response.headers()["deprecation"]);
+        assert_eq!(
+            format!(
+                "</v1/subscriptions/{}>; rel=\"successor-version\"",
+                subscription_id
+            ),
+            response.headers()[header::LINK]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscription_resource_test() {
+        // arrange
+        dotenv().ok();
+        let fake_email: String = helper_functions::unique_email();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let req_body = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            fake_email.clone(),
+            newsletter.newsletter_id,
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&req_body).unwrap()));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::CREATED, response.status());
+
+        // act
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!(
+                "/v1/subscriptions?email={}&include_pending=true",
+                fake_email
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("deprecation").is_none());
+        let by_email: GetSubscriptionsResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        let sub_id = by_email.resp[0].subscription_id.clone();
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/subscriptions/{}", sub_id))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let found = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::OK, found.status());
+        let found: Subscription = helper_functions::get_response(found.into_body())
+            .await
+            .unwrap();
+
+        let req = Request::builder()
+            .method(Method::DELETE)
+            .uri(format!("/v1/subscriptions/{}", sub_id))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let removed = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::OK, removed.status());
+        let removed: RemoveSubscriptionResponse =
+            helper_functions::get_response(removed.into_body())
+                .await
+                .unwrap();
+
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/subscriptions/{}", sub_id))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let gone = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(1, by_email.resp.len());
+        assert_eq!(Some(fake_email), found.email);
+        assert_eq!(sub_id, removed.subscription.subscription_id);
+        assert_eq!(StatusCode::NOT_FOUND, gone.status());
+    }
+
+    #[tokio::test]
+    async fn subscription_resource_invalid_request_test() {
+        // arrange
+        let app = api::app();
+
+        for (method, uri) in [
+            (Method::GET, "/v1/subscriptions"),
+            (Method::GET, "/v1/subscriptions?include_pending=true"),
+            (Method::GET, "/v1/subscriptions/not_uuid"),
+            (Method::DELETE, "/v1/subscriptions/not_uuid"),
+        ] {
+            // act
+            let req = Request::builder()
+                .method(method.clone())
+                .uri(uri)
+                .header(
+                    header::AUTHORIZATION,
+                    helper_functions::admin_authorization().await,
+                )
+                .body(body::Body::empty());
+            let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+            // assert
+            assert_eq!(
+                StatusCode::BAD_REQUEST,
+                response.status(),
+                "{} {}",
+                method,
+                uri
+            );
+        }
     }
 
     #[tokio::test]
     async fn get_subscriptions_test() {
         // arrange
         dotenv().ok();
-        let fake_email: String = SafeEmail().fake();
+        let fake_email: String = helper_functions::unique_email();
         let app = api::app();
-        let subscriptions: Vec<String> = vec![
-            "new_york_times".to_string(),
-            "columbus dispatch".to_string(),
+        let subscriptions = vec![
+            helper_functions::create_newsletter(&app).await,
+            helper_functions::create_newsletter(&app).await,
         ];
         for sub in subscriptions.clone() {
-            let req_body =
-                helper_functions::new_create_subscription_request(sub, fake_email.clone());
+            let req_body = helper_functions::new_create_subscription_request(
+                "Ydot19".to_string(),
+                fake_email.clone(),
+                sub.newsletter_id,
+            );
             let req = Request::builder()
                 .method(Method::POST)
-                .uri("/subscribe")
+                .uri("/v1/subscribe")
                 .header(header::CONTENT_TYPE, "application/json")
                 .body(body::Body::from(serde_json::to_string(&req_body).unwrap()));
 
@@ -135,10 +336,14 @@ mod subscription_integration_tests {
         }
 
         // prepare request
-        let payload = helper_functions::new_get_subscription_request(fake_email.clone());
+        let payload = helper_functions::new_get_subscription_request(fake_email.clone(), true);
         let req = Request::builder()
             .method(Method::GET)
-            .uri("/subscriptions")
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
         // act
@@ -164,7 +369,11 @@ mod subscription_integration_tests {
             helper_functions::new_remove_subscription_request(sub_id.clone());
         let req = Request::builder()
             .method(Method::DELETE)
-            .uri("/subscribe")
+            .uri("/v1/subscribe")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(
                 serde_json::to_string(&remove_subscription_request).unwrap(),
@@ -184,10 +393,14 @@ mod subscription_integration_tests {
         );
 
         // GET subscriptions
-        let payload = helper_functions::new_get_subscription_request(fake_email.clone());
+        let payload = helper_functions::new_get_subscription_request(fake_email.clone(), true);
         let req = Request::builder()
             .method(Method::GET)
-            .uri("/subscriptions")
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
             .header(header::CONTENT_TYPE, "application/json")
             .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
         // act
@@ -207,4 +420,413 @@ mod subscription_integration_tests {
             .collect();
         assert_eq!(subs.len(), 1);
     }
+
+    #[tokio::test]
+    async fn list_subscriptions_pages_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let mut created = Vec::new();
+        for _ in 0..3 {
+            let req_body = helper_functions::new_create_subscription_request(
+                "Ydot19".to_string(),
+                helper_functions::unique_email(),
+                newsletter.newsletter_id.clone(),
+            );
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri("/v1/subscribe")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body::Body::from(serde_json::to_string(&req_body).unwrap()));
+            let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+            assert_eq!(StatusCode::CREATED, response.status());
+            created.push(req_body.email);
+        }
+        let uri = format!(
+            "/v1/admin/subscriptions?newsletter_id={}&status=pending_confirmation&limit=2",
+            newsletter.newsletter_id
+        );
+
+        // act
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(uri.clone())
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        let first: ListSubscriptionsResponse = helper_functions::get_response(response.into_body())
+            .await
+            .unwrap();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!(
+                "{}&cursor={}",
+                uri,
+                first.next_cursor.clone().unwrap()
+            ))
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        let second: ListSubscriptionsResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+
+        // assert
+        assert_eq!(3, first.total);
+        assert_eq!(2, first.subscriptions.len());
+        assert_eq!(1, second.subscriptions.len());
+        assert!(second.next_cursor.is_none());
+        let listed: Vec<String> = first
+            .subscriptions
+            .into_iter()
+            .chain(second.subscriptions)
+            .map(|sub| sub.email.unwrap())
+            .collect();
+        assert_eq!(created, listed);
+    }
+
+    #[tokio::test]
+    async fn list_subscriptions_invalid_query_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+
+        for uri in [
+            "/v1/admin/subscriptions?limit=0",
+            "/v1/admin/subscriptions?status=unknown",
+            "/v1/admin/subscriptions?cursor=not-a-cursor",
+            "/v1/admin/subscriptions?subscribed_from=yesterday",
+        ] {
+            // act
+            let req = Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .header(
+                    header::AUTHORIZATION,
+                    helper_functions::admin_authorization().await,
+                )
+                .body(body::Body::empty());
+            let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+            // assert
+            assert_eq!(StatusCode::BAD_REQUEST, response.status(), "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn get_subscriptions_excludes_pending_test() {
+        // arrange
+        dotenv().ok();
+        let fake_email: String = helper_functions::unique_email();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let req_body = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            fake_email.clone(),
+            newsletter.newsletter_id,
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&req_body).unwrap()));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::CREATED, response.status());
+
+        let payload = helper_functions::new_get_subscription_request(fake_email.clone(), false);
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+        // act
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn confirm_subscription_unknown_token() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!(
+                "/v1/subscriptions/confirm?token={}",
+                Uuid::new_v4().simple()
+            ))
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn confirm_subscription_missing_token() {
+        // arrange
+        let app = api::app();
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/subscriptions/confirm")
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert!(response.status().is_client_error());
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let email: String = helper_functions::unique_email();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let payload = helper_functions::new_create_subscription_request(
+            "Ydot19".to_string(),
+            email.clone(),
+            newsletter.newsletter_id,
+        );
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/subscribe")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        assert_eq!(StatusCode::CREATED, response.status());
+        let get_payload = helper_functions::new_get_subscription_request(email.clone(), true);
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(
+                serde_json::to_string(&get_payload).unwrap(),
+            ));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+        let subscriptions: GetSubscriptionsResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        let subscription_id = subscriptions.resp[0].subscription_id.clone();
+        let token = helper_functions::unsubscribe_token(&subscription_id);
+
+        // act - following the link, as a link scanner would
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/unsubscribe?token={}", token))
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert - nothing is removed yet
+        assert_eq!(StatusCode::OK, response.status());
+        let preview: UnsubscribePreviewResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        assert_eq!(subscription_id, preview.subscription.subscription_id);
+
+        // act - one-click unsubscribe from a mail client
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(format!("/v1/unsubscribe?token={}", token))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(body::Body::from("List-Unsubscribe=One-Click"));
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+        let removed: RemoveSubscriptionResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        assert_eq!(subscription_id, removed.subscription.subscription_id);
+
+        // act - the link keeps pointing at a removed subscription
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/unsubscribe?token={}", token))
+            .body(body::Body::empty());
+        let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn unsubscribe_forged_token() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let token = format!("{}.{}", Uuid::new_v4().simple(), "00".repeat(32));
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/unsubscribe?token={}", token))
+            .body(body::Body::empty());
+
+        // act
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+
+        // assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+
+    #[tokio::test]
+    async fn subscription_invalid_fields_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let valid_email: String = helper_functions::unique_email();
+        let cases = [
+            ("Ydot19".to_string(), "".to_string(), "email"),
+            ("Ydot19".to_string(), "not-an-email".to_string(), "email"),
+            ("".to_string(), valid_email.clone(), "name"),
+            ("<script>".to_string(), valid_email.clone(), "name"),
+            ("a".repeat(257), valid_email, "name"),
+        ];
+
+        for (name, email, field) in cases {
+            let payload = helper_functions::new_create_subscription_request(
+                name,
+                email,
+                newsletter.newsletter_id.clone(),
+            );
+            let req = Request::builder()
+                .method(Method::POST)
+                .uri("/v1/subscribe")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+
+            // act
+            let response = app.clone().oneshot(req.unwrap()).await.unwrap();
+
+            // assert
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+            let body: serde_json::Value = helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+            assert_eq!(field, body["field"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn subscription_repeated_without_subscriber_name_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let email: String = helper_functions::unique_email();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let subscribe = |name: &str| {
+            let payload = helper_functions::new_create_subscription_request(
+                name.to_string(),
+                email.clone(),
+                newsletter.newsletter_id.clone(),
+            );
+            Request::builder()
+                .method(Method::POST)
+                .uri("/v1/subscribe")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body::Body::from(serde_json::to_string(&payload).unwrap()))
+                .unwrap()
+        };
+        let first = app.clone().oneshot(subscribe("Ydot19")).await.unwrap();
+        assert_eq!(StatusCode::CREATED, first.status());
+        helper_functions::clear_subscriber_name(&email);
+
+        // act - a subscription from before subscriber names has no name to clash with
+        let repeated = app
+            .clone()
+            .oneshot(subscribe("Someone Else"))
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, repeated.status());
+    }
+
+    #[tokio::test]
+    async fn subscription_repeated_test() {
+        // arrange
+        dotenv().ok();
+        let app = api::app();
+        let email: String = helper_functions::unique_email();
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let subscribe = |name: &str, email: String| {
+            let payload = helper_functions::new_create_subscription_request(
+                name.to_string(),
+                email,
+                newsletter.newsletter_id.clone(),
+            );
+            Request::builder()
+                .method(Method::POST)
+                .uri("/v1/subscribe")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body::Body::from(serde_json::to_string(&payload).unwrap()))
+                .unwrap()
+        };
+
+        // act
+        let first = app
+            .clone()
+            .oneshot(subscribe("Ydot19", email.clone()))
+            .await
+            .unwrap();
+        let repeated = app
+            .clone()
+            .oneshot(subscribe("Ydot19", email.to_uppercase()))
+            .await
+            .unwrap();
+        let renamed = app
+            .clone()
+            .oneshot(subscribe("Someone Else", email.clone()))
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(StatusCode::CREATED, first.status());
+        assert_eq!(StatusCode::OK, repeated.status());
+        assert_eq!(StatusCode::CONFLICT, renamed.status());
+        let payload = helper_functions::new_get_subscription_request(email, true);
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri("/v1/subscriptions")
+            .header(
+                header::AUTHORIZATION,
+                helper_functions::admin_authorization().await,
+            )
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body::Body::from(serde_json::to_string(&payload).unwrap()));
+        let response = app.oneshot(req.unwrap()).await.unwrap();
+        let subscriptions: GetSubscriptionsResponse =
+            helper_functions::get_response(response.into_body())
+                .await
+                .unwrap();
+        assert_eq!(1, subscriptions.resp.len());
+    }
 }
@@ -0,0 +1,3 @@
+#[path = "../common/mod.rs"]
+pub mod common;
+mod test_export;
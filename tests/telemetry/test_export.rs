@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod telemetry_tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::common::helper::helper_functions;
+    use axum::body::{self, Bytes};
+    use axum::http::{Method, Request, StatusCode};
+    use axum::routing::post;
+    use axum::{Extension, Router};
+    use dotenvy::dotenv;
+    use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+    use opentelemetry_proto::tonic::trace::v1::Span;
+    use prost::Message;
+    use service::api;
+    use tower::ServiceExt;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+    type Received = Arc<Mutex<Vec<Span>>>;
+
+    async fn export(Extension(received): Extension<Received>, body: Bytes) -> StatusCode {
+        let request = ExportTraceServiceRequest::decode(body).unwrap();
+        let spans = request
+            .resource_spans
+            .into_iter()
+            .flat_map(|r| r.scope_spans)
+            .flat_map(|s| s.spans);
+        received.lock().unwrap().extend(spans);
+        StatusCode::OK
+    }
+
+    /// stands in for an OTLP/HTTP collector, keeping every span it is sent
+    async fn start_collector() -> (String, Received) {
+        let received = Received::default();
+        let app = Router::new()
+            .route("/v1/traces", post(export))
+            .layer(Extension(received.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    fn attribute(span: &Span, key: &str) -> Option<String> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.as_ref()?.value.as_ref())
+            .and_then(|value| match value {
+                Value::StringValue(s) => Some(s.clone()),
+                _ => None,
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn exports_request_and_database_spans_test() {
+        // arrange
+        dotenv().ok();
+        let (collector, received) = start_collector().await;
+        let mut settings = api::Settings::load().unwrap();
+        settings.telemetry.otlp_endpoint = Some(collector);
+        let app = api::app_with_settings(&settings, api::Shutdown::new());
+        let newsletter = helper_functions::create_newsletter(&app).await;
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(format!("/v1/newsletters/{}", newsletter.newsletter_id))
+            .header(
+                "traceparent",
+                format!("00-{}-{}-01", TRACE_ID, PARENT_SPAN_ID),
+            )
+            .body(body::Body::empty())
+            .unwrap();
+
+        // act
+        let response = app.oneshot(req).await.unwrap();
+        let status = response.status();
+        let traceparent = response.headers()["traceparent"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        // the request span ends along with the response body
+        drop(response);
+        tokio::task::spawn_blocking(api::shutdown_telemetry)
+            .await
+            .unwrap();
+
+        // assert
+        assert_eq!(StatusCode::OK, status);
+        assert!(
+            traceparent.starts_with(&format!("00-{}-", TRACE_ID)),
+            "{}",
+            traceparent
+        );
+        assert!(!traceparent.contains(PARENT_SPAN_ID), "{}", traceparent);
+
+        let spans = received.lock().unwrap();
+        let in_trace: Vec<&Span> = spans
+            .iter()
+            .filter(|s| hex::encode(&s.trace_id) == TRACE_ID)
+            .collect();
+        let request = in_trace
+            .iter()
+            .find(|s| s.name == "http_request")
+            .expect("request span was not exported");
+        assert_eq!(PARENT_SPAN_ID, hex::encode(&request.parent_span_id));
+        assert!(traceparent.contains(&hex::encode(&request.span_id)));
+        let children: Vec<&&Span> = in_trace
+            .iter()
+            .filter(|s| s.parent_span_id == request.span_id)
+            .collect();
+        assert!(
+            children.iter().any(|s| s.name == "db.pool.checkout"),
+            "{:?}",
+            children.iter().map(|s| &s.name).collect::<Vec<_>>()
+        );
+        let query = children
+            .iter()
+            .find(|s| s.name == "db.query")
+            .expect("query span was not exported");
+        let statement = attribute(query, "db.statement").unwrap();
+        assert!(statement.contains("newsletters"), "{}", statement);
+        assert!(
+            !statement.contains(&newsletter.newsletter_id),
+            "{}",
+            statement
+        );
+    }
+}